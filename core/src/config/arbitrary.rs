@@ -0,0 +1,134 @@
+//! Random configs for property tests.
+
+use crate::config::{
+    BitRate, ButtonState, ConfigItem, Dimension, Millis, MouseBind, OptionKey, OptionValue,
+    ScrcpyOptions, Seconds, ShortcutMod, OPTIONS,
+};
+use fastrand::Rng;
+
+const CHARS: &[char] = &[
+    'a', 'Z', '0', '9', '-', '_', '.', ':', '/', ',', '=', '+', '?', '@', ' ', '\t', '\'', '"',
+    '\\', '$', '`', '*', '#', ';', '&', '|', '(', 'é', '中',
+];
+
+pub fn string(rng: &mut Rng) -> String {
+    (0..rng.usize(..12))
+        .map(|_| CHARS[rng.usize(..CHARS.len())])
+        .collect()
+}
+
+pub fn pick<T: ButtonState>(rng: &mut Rng) -> T {
+    let mut states = T::states();
+    states.swap_remove(rng.usize(..states.len()))
+}
+
+fn option<T>(rng: &mut Rng, value: impl FnOnce(&mut Rng) -> T) -> Option<T> {
+    rng.bool().then(|| value(rng))
+}
+
+/// A value of one of the integer units, in range.
+macro_rules! unit {
+    ($rng:expr, $unit:ident) => {
+        $unit::new($rng.u32($unit::MIN..=$unit::MAX)).unwrap()
+    };
+}
+
+fn bit_rate(rng: &mut Rng) -> BitRate {
+    BitRate::new(rng.u32(1..=BitRate::MAX.bits_per_second())).unwrap()
+}
+
+/// An ordered list without repeats, as read back by `from_config_list`.
+fn list<T: ButtonState>(rng: &mut Rng) -> Vec<T> {
+    let mut states = T::states();
+    rng.shuffle(&mut states);
+    states.truncate(rng.usize(..=states.len()));
+    states
+}
+
+/// A config using every field, in the shape it has after loading: lists
+/// without repeats and environment keys that are set, trimmed and sorted.
+pub fn config(rng: &mut Rng) -> ConfigItem {
+    let mut env: Vec<(String, String)> = (0..rng.usize(..4))
+        .map(|i| (format!("VAR_{i}"), string(rng)))
+        .collect();
+    env.sort();
+    let mut config = ConfigItem {
+        language: pick(rng),
+        executable: Some(string(rng)),
+        serial: string(rng),
+        connect_method: pick(rng),
+        port: string(rng),
+        auto_reconnect: rng.bool(),
+        reconnect_retries: rng.u32(..),
+        video_source: pick(rng),
+        video_playback: rng.bool(),
+        video_codec: pick(rng),
+        video_codec_options: string(rng),
+        video_codec_fallback: list(rng),
+        orientation_type: pick(rng),
+        orientation_angle: pick(rng),
+        orientation_lock: rng.bool(),
+        orientation_flip: rng.bool(),
+        render_driver: pick(rng),
+        audio_source: pick(rng),
+        audio_playback: rng.bool(),
+        audio_codec: pick(rng),
+        audio_codec_options: string(rng),
+        audio_codec_fallback: list(rng),
+        fps: option(rng, |rng| rng.u32(..)),
+        video_buffer: option(rng, |rng| unit!(rng, Millis)),
+        buffer_flag: pick(rng),
+        buffer_sync: rng.bool(),
+        keyboard: pick(rng),
+        mouse: pick(rng),
+        shortcut_mod: ShortcutMod::states()
+            .into_iter()
+            .filter(|_| rng.bool())
+            .collect(),
+        key_inject: pick(rng),
+        mouse_bind: option(rng, |rng| std::array::from_fn(|_| pick::<MouseBind>(rng))),
+        virtual_display: rng.bool(),
+        display_height: option(rng, |rng| unit!(rng, Dimension)),
+        display_width: option(rng, |rng| unit!(rng, Dimension)),
+        start_app: string(rng),
+        restart_app: rng.bool(),
+        app_name_type: pick(rng),
+        // passed through verbatim, so only well-formed command lines
+        additional_args: shell_words::join((0..rng.usize(..3)).map(|_| string(rng))),
+        pre_launch_hook: string(rng),
+        post_exit_hook: string(rng),
+        hook_timeout: unit!(rng, Seconds),
+        hook_failure: pick(rng),
+        env,
+        options: Default::default(),
+    };
+    for spec in OPTIONS {
+        let value = match config.options.get(spec.key) {
+            OptionValue::Switch(_) => OptionValue::Switch(rng.bool()),
+            OptionValue::Text(_) => OptionValue::Text(string(rng)),
+            OptionValue::Number(_) if spec.key == OptionKey::TunnelPort => {
+                OptionValue::Number(option(rng, |rng| rng.u16(..).into()))
+            }
+            OptionValue::Number(_) if spec.key == OptionKey::TimeLimit => {
+                OptionValue::Number(option(rng, |rng| unit!(rng, Seconds).get()))
+            }
+            OptionValue::Number(_)
+                if matches!(spec.key, OptionKey::V4l2Buffer | OptionKey::AudioBuffer) =>
+            {
+                OptionValue::Number(option(rng, |rng| unit!(rng, Millis).get()))
+            }
+            OptionValue::Number(_) if spec.key == OptionKey::VideoSize => {
+                OptionValue::Number(option(rng, |rng| unit!(rng, Dimension).get()))
+            }
+            OptionValue::Number(_) => OptionValue::Number(option(rng, |rng| rng.u32(..))),
+            OptionValue::BitRate(_) => OptionValue::BitRate(option(rng, bit_rate)),
+            OptionValue::Decimal(_) => OptionValue::Decimal(option(rng, |rng| rng.f32() * 360.0)),
+            OptionValue::Choice(_) => {
+                let choices = ScrcpyOptions::choices(spec.key);
+                OptionValue::Choice(choices[rng.usize(..choices.len())].value.clone())
+            }
+        };
+        config.options.set(spec.key, value);
+    }
+    config
+}
@@ -0,0 +1,118 @@
+use crate::config::{FieldValue, UnitError};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A bit rate in scrcpy's syntax: bits per second, optionally with a `K`
+/// (×1000) or `M` (×1000000) suffix, e.g. `8M`, `128K` or `2000000`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BitRate(u32);
+
+impl BitRate {
+    /// What scrcpy uses without `--video-bit-rate`.
+    pub const DEFAULT_VIDEO: Self = Self(8_000_000);
+    /// What scrcpy uses without `--audio-bit-rate`.
+    pub const DEFAULT_AUDIO: Self = Self(128_000);
+    /// Raw audio: 48 kHz, 2 channels, 16 bits.
+    pub const RAW_AUDIO: Self = Self(1_536_000);
+    /// scrcpy stores bit rates in an `int`.
+    pub const MAX: Self = Self(i32::MAX as u32);
+
+    pub fn new(bits_per_second: u32) -> Option<Self> {
+        (1..=Self::MAX.0)
+            .contains(&bits_per_second)
+            .then_some(Self(bits_per_second))
+    }
+
+    pub fn bits_per_second(self) -> u32 {
+        self.0
+    }
+}
+
+impl FromStr for BitRate {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (digits, multiplier) = match s.char_indices().last() {
+            Some((i, 'k' | 'K')) => (&s[..i], 1_000),
+            Some((i, 'm' | 'M')) => (&s[..i], 1_000_000),
+            _ => (s, 1),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(UnitError::BitRateSyntax);
+        }
+        digits
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(multiplier))
+            .and_then(|n| u32::try_from(n).ok())
+            .and_then(Self::new)
+            .ok_or(UnitError::BitRateSyntax)
+    }
+}
+
+impl FieldValue for BitRate {
+    fn parse_field(s: &str) -> Result<Self, UnitError> {
+        s.parse()
+    }
+}
+
+impl Display for BitRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            n if n.is_multiple_of(1_000_000) => write!(f, "{}M", n / 1_000_000),
+            n if n.is_multiple_of(1_000) => write!(f, "{}K", n / 1_000),
+            n => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Saved in scrcpy's syntax, e.g. `video_bit_rate = "8M"`.
+impl Serialize for BitRate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BitRate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scrcpy_syntax() {
+        assert_eq!("8M".parse(), Ok(BitRate(8_000_000)));
+        assert_eq!(" 128k ".parse(), Ok(BitRate(128_000)));
+        assert_eq!("2000000".parse(), Ok(BitRate(2_000_000)));
+        assert_eq!("2147483647".parse(), Ok(BitRate::MAX));
+        for invalid in ["", "M", "0", "-8M", "8.5M", "8 M", "8G", "2148M", "8MB"] {
+            assert_eq!(
+                invalid.parse::<BitRate>(),
+                Err(UnitError::BitRateSyntax),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn displays_in_scrcpy_syntax() {
+        for s in ["8M", "128K", "1500", "2147483647"] {
+            assert_eq!(s.parse::<BitRate>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn blank_keeps_the_default() {
+        assert_eq!(BitRate::parse_optional("  "), Ok(None));
+        assert_eq!(BitRate::parse_optional("4M"), Ok(BitRate::new(4_000_000)));
+        assert!(BitRate::parse_optional("fast").is_err());
+    }
+}
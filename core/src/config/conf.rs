@@ -0,0 +1,969 @@
+use crate::config::{
+    find_adb, find_scrcpy_installs, lenient, Dimension, Millis, ScrcpyOptions, ScrcpyOptionsRaw,
+    Seconds,
+};
+use crate::i18n::{Language, LANGUAGE};
+use crate::{t, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env::current_dir;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+#[inline]
+fn config_path() -> PathBuf {
+    let xdg_config_home = env::var("XDG_CONFIG_HOME").unwrap_or_default();
+    if xdg_config_home.trim().is_empty() {
+        home::home_dir()
+            .map(|home| home.join(".config"))
+            .unwrap_or_else(|| current_dir().unwrap_or_default())
+    } else {
+        PathBuf::from(xdg_config_home)
+    }
+    .join("scrcpy-wrapper.toml")
+}
+
+#[inline]
+pub(crate) fn history_path() -> PathBuf {
+    config_path().with_file_name("scrcpy-wrapper-history.toml")
+}
+
+/// How often a dropped session is restarted unless the profile says otherwise.
+pub const DEFAULT_RECONNECT_RETRIES: u32 = 5;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigItemRaw {
+    pub language: Option<String>,
+    pub executable: Option<String>,
+    pub serial: Option<String>,
+    pub connect_method: Option<String>,
+    pub port: Option<String>,
+    pub auto_reconnect: Option<bool>,
+    pub reconnect_retries: Option<u32>,
+    pub video_source: Option<String>,
+    pub video_playback: Option<bool>,
+    pub video_codec: Option<String>,
+    pub video_codec_options: Option<String>,
+    pub video_codec_fallback: Option<String>,
+    pub orientation_type: Option<String>,
+    pub orientation_angle: Option<String>,
+    pub orientation_lock: Option<bool>,
+    pub orientation_flip: Option<bool>,
+    pub render_driver: Option<String>,
+    pub audio_source: Option<String>,
+    pub audio_playback: Option<bool>,
+    pub audio_codec: Option<String>,
+    pub audio_codec_options: Option<String>,
+    pub audio_codec_fallback: Option<String>,
+    pub fps: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub video_buffer: Option<Millis>,
+    pub buffer_flag: Option<String>,
+    pub buffer_sync: Option<bool>,
+    pub keyboard: Option<String>,
+    pub mouse: Option<String>,
+    pub shortcut_mod: Option<String>,
+    pub key_inject: Option<String>,
+    pub mouse_bind: Option<String>,
+    pub virtual_display: Option<bool>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_height: Option<Dimension>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_width: Option<Dimension>,
+    pub start_app: Option<String>,
+    pub restart_app: Option<bool>,
+    pub app_name_type: Option<String>,
+    pub additional_args: Option<String>,
+    pub pre_launch_hook: Option<String>,
+    pub post_exit_hook: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub hook_timeout: Option<Seconds>,
+    pub hook_failure: Option<String>,
+    pub env: Option<BTreeMap<String, String>>,
+    #[serde(flatten)]
+    pub options: ScrcpyOptionsRaw,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigItem {
+    pub language: Language,
+    pub executable: Option<String>,
+    pub serial: String,
+    pub connect_method: ConnectMethod,
+    pub port: String,
+    pub auto_reconnect: bool,
+    pub reconnect_retries: u32,
+    pub video_source: VideoSource,
+    pub video_playback: bool,
+    pub video_codec: VideoCodec,
+    pub video_codec_options: String,
+    pub video_codec_fallback: Vec<VideoCodec>,
+    pub orientation_type: OrientationType,
+    pub orientation_angle: OrientationAngle,
+    pub orientation_lock: bool,
+    pub orientation_flip: bool,
+    pub render_driver: RenderDriver,
+    pub audio_source: AudioSource,
+    pub audio_playback: bool,
+    pub audio_codec: AudioCodec,
+    pub audio_codec_options: String,
+    pub audio_codec_fallback: Vec<AudioCodec>,
+    pub fps: Option<u32>,
+    pub video_buffer: Option<Millis>,
+    pub buffer_flag: BufferFlag,
+    pub buffer_sync: bool,
+    pub keyboard: Keyboard,
+    pub mouse: Mouse,
+    pub shortcut_mod: Vec<ShortcutMod>,
+    pub key_inject: KeyInject,
+    pub mouse_bind: Option<[MouseBind; 8]>,
+    pub virtual_display: bool,
+    pub display_height: Option<Dimension>,
+    pub display_width: Option<Dimension>,
+    pub start_app: String,
+    pub restart_app: bool,
+    pub app_name_type: AppNameType,
+    pub additional_args: String,
+    pub pre_launch_hook: String,
+    pub post_exit_hook: String,
+    /// How long a hook may run before it is killed.
+    pub hook_timeout: Seconds,
+    pub hook_failure: HookFailure,
+    /// Extra environment for scrcpy, adb and the hooks; rows with an empty
+    /// key are kept while editing but not saved.
+    pub env: Vec<(String, String)>,
+    pub options: ScrcpyOptions,
+}
+impl ConfigItemRaw {
+    /// The profile with defaults filled in; a profile without a language
+    /// takes the UI's. Nothing is detected or applied here, see
+    /// [`Config::detect_installs`] and [`ConfigItem::apply_language`].
+    pub fn to_config(&self) -> ConfigItem {
+        let language = match self.language.as_deref() {
+            Some("zh") => Language::Zh,
+            Some("en") => Language::En,
+            _ => *LANGUAGE.read().unwrap(),
+        };
+
+        ConfigItem {
+            language,
+            executable: self.executable.clone(),
+            serial: self.serial.clone().unwrap_or_default(),
+            connect_method: ConnectMethod::from_config_str(&self.connect_method),
+            port: self.port.clone().unwrap_or_default(),
+            auto_reconnect: self.auto_reconnect.unwrap_or_default(),
+            reconnect_retries: self.reconnect_retries.unwrap_or(DEFAULT_RECONNECT_RETRIES),
+            video_source: VideoSource::from_config_str(&self.video_source),
+            video_playback: self.video_playback.unwrap_or(true),
+            video_codec: VideoCodec::from_config_str(&self.video_codec),
+            video_codec_options: self.video_codec_options.clone().unwrap_or_default(),
+            video_codec_fallback: from_config_list(&self.video_codec_fallback),
+            orientation_type: OrientationType::from_config_str(&self.orientation_type),
+            orientation_angle: OrientationAngle::from_config_str(&self.orientation_angle),
+            orientation_lock: self.orientation_lock.unwrap_or_default(),
+            orientation_flip: self.orientation_flip.unwrap_or_default(),
+            render_driver: RenderDriver::from_config_str(&self.render_driver),
+            audio_source: AudioSource::from_config_str(&self.audio_source),
+            audio_playback: self.audio_playback.unwrap_or(true),
+            audio_codec: AudioCodec::from_config_str(&self.audio_codec),
+            audio_codec_options: self.audio_codec_options.clone().unwrap_or_default(),
+            audio_codec_fallback: from_config_list(&self.audio_codec_fallback),
+            fps: self.fps,
+            video_buffer: self.video_buffer,
+            buffer_flag: BufferFlag::from_config_str(&self.buffer_flag),
+            buffer_sync: self.buffer_sync.unwrap_or(true),
+            keyboard: Keyboard::from_config_str(&self.keyboard),
+            mouse: Mouse::from_config_str(&self.mouse),
+            shortcut_mod: from_config_list(&self.shortcut_mod),
+            key_inject: KeyInject::from_config_str(&self.key_inject),
+            mouse_bind: MouseBind::from_config_bindings(&self.mouse_bind),
+            virtual_display: self.virtual_display.unwrap_or_default(),
+            display_height: self.display_height,
+            display_width: self.display_width,
+            start_app: self.start_app.clone().unwrap_or_default(),
+            restart_app: self.restart_app.unwrap_or_default(),
+            app_name_type: AppNameType::from_config_str(&self.app_name_type),
+            additional_args: self.additional_args.clone().unwrap_or_default(),
+            pre_launch_hook: self.pre_launch_hook.clone().unwrap_or_default(),
+            post_exit_hook: self.post_exit_hook.clone().unwrap_or_default(),
+            hook_timeout: self.hook_timeout.unwrap_or(Seconds::DEFAULT_HOOK_TIMEOUT),
+            hook_failure: HookFailure::from_config_str(&self.hook_failure),
+            env: self.env.clone().unwrap_or_default().into_iter().collect(),
+            options: self.options.to_options(),
+        }
+    }
+}
+
+impl ConfigItem {
+    /// Switches the UI language to the profile's.
+    pub fn apply_language(&self) {
+        *LANGUAGE.write().unwrap() = self.language;
+    }
+
+    /// The profile's environment variables, skipping rows without a key.
+    pub fn env_vars(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.env
+            .iter()
+            .filter(|(key, _)| !key.trim().is_empty())
+            .map(|(key, value)| (key.trim(), value.as_str()))
+    }
+
+    pub fn to_raw(&self) -> ConfigItemRaw {
+        ConfigItemRaw {
+            language: Some(self.language.to_config_string()),
+            executable: self.executable.clone(),
+            serial: Some(self.serial.clone()),
+            connect_method: Some(self.connect_method.to_config_string()),
+            port: Some(self.port.clone()),
+            auto_reconnect: Some(self.auto_reconnect),
+            reconnect_retries: Some(self.reconnect_retries),
+            video_source: Some(self.video_source.to_config_string()),
+            video_playback: Some(self.video_playback),
+            video_codec: Some(self.video_codec.to_config_string()),
+            video_codec_options: Some(self.video_codec_options.clone()),
+            video_codec_fallback: Some(to_config_list(&self.video_codec_fallback)),
+            orientation_type: Some(self.orientation_type.to_config_string()),
+            orientation_angle: Some(self.orientation_angle.to_config_string()),
+            orientation_lock: Some(self.orientation_lock),
+            orientation_flip: Some(self.orientation_flip),
+            render_driver: Some(self.render_driver.to_config_string()),
+            audio_source: Some(self.audio_source.to_config_string()),
+            audio_playback: Some(self.audio_playback),
+            audio_codec: Some(self.audio_codec.to_config_string()),
+            audio_codec_options: Some(self.audio_codec_options.clone()),
+            audio_codec_fallback: Some(to_config_list(&self.audio_codec_fallback)),
+            fps: self.fps,
+            video_buffer: self.video_buffer,
+            buffer_flag: Some(self.buffer_flag.to_config_string()),
+            buffer_sync: Some(self.buffer_sync),
+            keyboard: Some(self.keyboard.to_config_string()),
+            mouse: Some(self.mouse.to_config_string()),
+            shortcut_mod: Some(to_config_list(&self.shortcut_mod)),
+            key_inject: Some(self.key_inject.to_config_string()),
+            mouse_bind: Some(
+                self.mouse_bind
+                    .as_ref()
+                    .map(MouseBind::to_config_bindings)
+                    .unwrap_or_default(),
+            ),
+            virtual_display: Some(self.virtual_display),
+            display_height: self.display_height,
+            display_width: self.display_width,
+            start_app: Some(self.start_app.clone()),
+            restart_app: Some(self.restart_app),
+            app_name_type: Some(self.app_name_type.to_config_string()),
+            additional_args: Some(self.additional_args.clone()),
+            pre_launch_hook: Some(self.pre_launch_hook.clone()),
+            post_exit_hook: Some(self.post_exit_hook.clone()),
+            hook_timeout: Some(self.hook_timeout),
+            hook_failure: Some(self.hook_failure.to_config_string()),
+            env: Some(
+                self.env_vars()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            ),
+            options: self.options.to_raw(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigRaw {
+    pub version: Option<u32>,
+    pub adb: Option<String>,
+    pub default: Option<ConfigItemRaw>,
+    pub saved: Option<HashMap<String, ConfigItemRaw>>,
+    pub rules: Option<Vec<DeviceRuleRaw>>,
+    pub control_api: Option<bool>,
+    pub control_socket: Option<String>,
+}
+
+impl ConfigRaw {
+    /// The config with defaults filled in. Profiles without an executable
+    /// keep none until [`Config::detect_installs`] runs.
+    pub fn to_config(&self) -> Config {
+        Config {
+            adb: self.adb.clone().filter(|adb| !adb.trim().is_empty()),
+            detected_adb: None,
+            default: self.default.clone().unwrap_or_default().to_config(),
+            saved: self
+                .saved
+                .clone()
+                .unwrap_or_default()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_config()))
+                .collect(),
+            rules: self
+                .rules
+                .clone()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(DeviceRuleRaw::to_rule)
+                .collect(),
+            control_api: self.control_api.unwrap_or_default(),
+            control_socket: self.control_socket.clone(),
+        }
+    }
+
+    /// Reads `scrcpy-wrapper.toml`, upgrading files from before named
+    /// profiles existed.
+    pub fn load() -> Result<Self> {
+        if Path::new(&config_path()).exists() {
+            let mut file = File::open(config_path())?;
+            let mut toml_str = String::new();
+            file.read_to_string(&mut toml_str)?;
+            let t: ConfigRaw = toml::from_str(toml_str.as_str())?;
+            if t.version.is_none() {
+                let t: ConfigItemRaw = toml::from_str(toml_str.as_str())?;
+                return Ok(Self {
+                    version: Some(1),
+                    adb: None,
+                    default: Some(t),
+                    saved: None,
+                    rules: None,
+                    control_api: None,
+                    control_socket: None,
+                });
+            }
+            Ok(t)
+        } else {
+            Err(Error::ConfigNotFound(config_path()))
+        }
+    }
+
+    pub fn dump(&self) -> Result<()> {
+        if let Some(parent) = config_path().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(config_path())?;
+        file.write_all(toml::to_string(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// adb set by the user, see [`adb`](Self::adb).
+    pub adb: Option<String>,
+    /// adb found on this machine; not saved, so it is looked up again when
+    /// it moves.
+    pub detected_adb: Option<String>,
+    pub default: ConfigItem,
+    pub saved: HashMap<String, ConfigItem>,
+    pub rules: Vec<DeviceRule>,
+    /// Whether the control API listens while the wrapper runs.
+    pub control_api: bool,
+    /// Socket of the control API, see [`control_socket`](crate::util::control_socket).
+    pub control_socket: Option<String>,
+}
+
+impl Config {
+    /// adb used by the wrapper and passed to scrcpy as `ADB`: the one set
+    /// by the user, else the detected one.
+    pub fn adb(&self) -> Option<&str> {
+        self.adb.as_deref().or(self.detected_adb.as_deref())
+    }
+
+    /// Fills in what the file leaves to this machine: the first scrcpy
+    /// found for profiles without an executable, and the adb next to the
+    /// default profile's scrcpy. Scans the filesystem.
+    pub fn detect_installs(&mut self) {
+        let scrcpy = find_scrcpy_installs().into_iter().next();
+        for item in iter::once(&mut self.default).chain(self.saved.values_mut()) {
+            if item.executable.is_none() {
+                item.executable = scrcpy.clone();
+            }
+        }
+        self.detected_adb = find_adb(&self.default.executable);
+    }
+
+    pub fn to_raw(&self) -> ConfigRaw {
+        ConfigRaw {
+            version: Some(1),
+            adb: self.adb.clone(),
+            default: Some(self.default.to_raw()),
+            saved: Some(
+                self.saved
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_raw()))
+                    .collect(),
+            ),
+            rules: Some(self.rules.iter().map(DeviceRule::to_raw).collect()),
+            control_api: Some(self.control_api),
+            control_socket: self.control_socket.clone(),
+        }
+    }
+
+    /// The saved profile of the first rule matching the device, with its name.
+    pub fn profile_for_device(
+        &self,
+        serial: &str,
+        model: &str,
+        sdk: u32,
+    ) -> Option<(&str, &ConfigItem)> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(serial, model, sdk))
+            .find_map(|rule| {
+                self.saved
+                    .get(&rule.profile)
+                    .map(|profile| (rule.profile.as_str(), profile))
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceRuleRaw {
+    pub serial: Option<String>,
+    pub model: Option<String>,
+    pub min_sdk: Option<u32>,
+    pub profile: Option<String>,
+}
+
+impl DeviceRuleRaw {
+    fn to_rule(&self) -> Option<DeviceRule> {
+        Some(DeviceRule {
+            serial: self.serial.clone().filter(|s| !s.trim().is_empty()),
+            model: self.model.clone().filter(|m| !m.trim().is_empty()),
+            min_sdk: self.min_sdk,
+            profile: self.profile.clone().filter(|p| !p.trim().is_empty())?,
+        })
+    }
+}
+
+/// Loads `profile` for devices matching every criterion that is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceRule {
+    pub serial: Option<String>,
+    /// Glob on `ro.product.model`, e.g. `SM-T*`.
+    pub model: Option<String>,
+    pub min_sdk: Option<u32>,
+    pub profile: String,
+}
+
+impl DeviceRule {
+    pub fn to_raw(&self) -> DeviceRuleRaw {
+        DeviceRuleRaw {
+            serial: self.serial.clone(),
+            model: self.model.clone(),
+            min_sdk: self.min_sdk,
+            profile: Some(self.profile.clone()),
+        }
+    }
+
+    pub fn matches(&self, serial: &str, model: &str, sdk: u32) -> bool {
+        self.serial.as_ref().is_none_or(|s| s == serial)
+            && self.model.as_ref().is_none_or(|m| glob_match(m, model))
+            && self.min_sdk.is_none_or(|min| sdk >= min)
+    }
+}
+
+impl Display for DeviceRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut criteria = vec![];
+        if let Some(serial) = &self.serial {
+            criteria.push(format!("serial = {serial}"));
+        }
+        if let Some(model) = &self.model {
+            criteria.push(format!("model = {model}"));
+        }
+        if let Some(min_sdk) = self.min_sdk {
+            criteria.push(format!("SDK >= {min_sdk}"));
+        }
+        if criteria.is_empty() {
+            criteria.push("*".to_string());
+        }
+        write!(f, "{} → {}", criteria.join(", "), self.profile)
+    }
+}
+
+/// Case-insensitive match supporting `*` and `?`.
+fn glob_match(pattern: &str, s: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let s: Vec<char> = s.to_lowercase().chars().collect();
+    let (mut p, mut i) = (0, 0);
+    let mut backtrack = None;
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, i));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            i = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Enums whose variants can be listed, e.g. to cycle through them on a
+/// button or offer them in a pick list.
+pub trait ButtonState {
+    fn states() -> Vec<Self>
+    where
+        Self: Sized;
+}
+
+pub trait ConfigEnum {
+    fn from_config_str(s: &Option<String>) -> Self;
+    fn to_config_string(&self) -> String;
+}
+
+/// Parses an ordered, comma separated list such as `h265,h264`, skipping
+/// unknown and repeated entries.
+pub fn from_config_list<T: ConfigEnum + ButtonState + Copy + PartialEq>(
+    s: &Option<String>,
+) -> Vec<T> {
+    let states = T::states();
+    let mut list = Vec::new();
+    for key in s.as_deref().unwrap_or_default().split(',') {
+        if let Some(value) = states.iter().find(|v| v.to_config_string() == key.trim()) {
+            if !list.contains(value) {
+                list.push(*value);
+            }
+        }
+    }
+    list
+}
+
+pub fn to_config_list<T: ConfigEnum>(list: &[T]) -> String {
+    list.iter()
+        .map(|v| v.to_config_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[macro_export]
+macro_rules! config_enum {
+    (
+        $(pub enum $name:ident {
+            $(
+                $(#[$meta:meta])?
+                $variant:ident: $label:expr, $display:expr,
+            )*
+        })*
+    ) => {$(
+        #[derive(Copy, Clone, PartialEq, Debug, Default)]
+        pub enum $name {
+            $(
+                $(#[$meta])?
+                $variant,
+            )*
+        }
+
+        impl $crate::config::ButtonState for $name {
+            fn states() -> Vec<Self>
+            where
+                Self: Sized,
+            {
+                vec![$(
+                    $name::$variant,
+                )*]
+            }
+        }
+
+        impl $crate::config::ConfigEnum for $name {
+            fn from_config_str(s: &Option<String>) -> Self {
+                match s {
+                    Some(s) => match s.as_str() {
+                        $(
+                            $label => $name::$variant,
+                        )*
+                        _ => $name::default(),
+                    },
+                    None => $name::default(),
+                }
+            }
+
+            fn to_config_string(&self) -> String {
+                match self {
+                    $(
+                        $name::$variant => $label.to_string(),
+                    )*
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(
+                    f,
+                    "{}",
+                    match self {
+                        $(
+                            $name::$variant => $display,
+                        )*
+                    }
+                )
+            }
+        })*
+    };
+}
+
+config_enum! {
+    pub enum ConnectMethod {
+        #[default]
+        Adb: "adb", "ADB",
+        Otg: "otg", "OTG",
+    }
+
+    pub enum VideoSource {
+        No: "no", t! {zh: "无",en: "no video"}.to_string(),
+        #[default]
+        Display: "display", t! {zh: "屏幕",en: "display"}.to_string(),
+        Camera:"camera",  t! {zh: "摄像头 (Android 12+)",en: "camera (Android 12+)"}.to_string(),
+    }
+
+    pub enum Camera {
+        #[default]
+        Default: "default", t! {zh: "默认",en: "default"}.to_string(),
+        Front:"front", t! {zh: "前置",en: "front"}.to_string(),
+        Back:"back", t! {zh: "后置",en: "back"}.to_string(),
+        External:"external", t! {zh: "外置",en: "external"}.to_string(),
+    }
+
+    pub enum VideoCodec{
+        #[default]
+        H264: "h264", "h264",
+        H265: "h265", "h265",
+        Av1: "av1", "av1",
+    }
+
+    pub enum OrientationType{
+        #[default]
+        Client: "client", t! {zh: "客户端",en: "client"}.to_string(),
+        Capture: "capture", t! {zh: "捕获",en: "capture"}.to_string(),
+        Display: "display", t! {zh: "显示",en: "display"}.to_string(),
+    }
+
+    pub enum RenderDriver{
+        #[default]
+        Default: "default", t! {zh: "默认",en: "default"}.to_string(),
+        OpenGl: "opengl", "OpenGL".to_string(),
+        OpenGlEs2: "opengles2", "OpenGL ES 2".to_string(),
+        OpenGlEs: "opengles", "OpenGL ES".to_string(),
+        Metal: "metal", "Metal".to_string(),
+        Direct3d: "direct3d", "Direct3D".to_string(),
+        Software: "software", t! {zh: "软件",en: "software"}.to_string(),
+    }
+
+    pub enum BufferFlag{
+        #[default]
+        Video: "video", "--video-buffer".to_string(),
+        Display: "display", "--display-buffer".to_string(),
+    }
+
+    pub enum OrientationAngle {
+        #[default]
+        Default:"default", t! {zh: "默认",en: "default"}.to_string(),
+        _0: "0", "0°".to_string(),
+        _90:"90", "90°".to_string(),
+        _180: "180", "180°".to_string(),
+        _270: "270", "270°".to_string(),
+    }
+
+    pub enum AudioSource {
+        No: "no", t! {zh: "无音频",en: "No audio"}.to_string(),
+        #[default]
+        Output: "output", t! {zh: "音频输出 (Android 11+)",en: "Audio output (Android 11+)"}.to_string(),
+        Playback: "playback", "Playback (Android 13+)".to_string(),
+        Mic: "mic", t!{zh: "麦克风",en: "Microphone"}.to_string(),
+        MicUnprocessed: "mic-unprocessed", t!{zh: "麦克风 (未处理)",en: "Microphone (unprocessed)"}.to_string(),
+        MicCamcorder: "mic-camcorder", t!{zh: "麦克风 (摄像机)",en: "Microphone (camcorder)"}.to_string(),
+        MicVoiceRecognition: "mic-voice-recognition", t!{zh: "麦克风 (语音识别)",en: "Microphone (voice recognition)"}.to_string(),
+        MicVoiceCommunication: "mic-voice-communication", t!{zh: "麦克风 (语音通信)",en: "Microphone (voice communication)"}.to_string(),
+        VoiceCall: "voice-call", t!{zh: "语音通话",en: "Voice call"}.to_string(),
+        VoiceCallUplink: "voice-call-uplink", t!{zh: "语音通话 (上行)",en: "Voice call (uplink)"}.to_string(),
+        VoiceCallDownlink: "voice-call-downlink", t!{zh: "语音通话 (下行)",en: "Voice call (downlink)"}.to_string(),
+        VoicePerformance: "voice-performance", t!{zh: "语音性能",en: "Voice performance"}.to_string(),
+    }
+
+    pub enum AudioCodec {
+        #[default]
+        Opus: "opus", "opus",
+        Aac: "aac", "aac",
+        Flac: "flac", "flac",
+        Raw: "raw", "raw",
+    }
+
+    pub enum Keyboard{
+        #[default]
+        Sdk: "sdk", "SDK".to_string(),
+        Uhid: "uhid", "UHID".to_string(),
+        Aoa: "aoa", "AOA".to_string(),
+        Disabled: "disabled", t! {zh: "禁用",en: "Disabled"}.to_string(),
+    }
+
+    pub enum Mouse{
+        #[default]
+        Sdk: "sdk", "SDK".to_string(),
+        Uhid: "uhid", "UHID".to_string(),
+        Aoa: "aoa", "AOA".to_string(),
+        Disabled: "disabled", t! {zh: "禁用",en: "Disabled"}.to_string(),
+    }
+
+    pub enum Gamepad{
+        #[default]
+        Disabled: "disabled", t! {zh: "禁用",en: "Disabled"}.to_string(),
+        Uhid: "uhid", "UHID".to_string(),
+        Aoa: "aoa", "AOA".to_string(),
+    }
+
+    pub enum KeyInject{
+        #[default]
+        Mixed: "mixed", t! {zh: "混合",en: "Mixed"}.to_string(),
+        PreferText: "prefer-text", t! {zh: "优先文本",en: "Prefer text"}.to_string(),
+        RawKeyEvents: "raw-key-events", t! {zh: "原始按键事件",en: "Raw key events"}.to_string(),
+    }
+
+    pub enum ShortcutMod{
+        #[default]
+        LAlt: "lalt", t! {zh: "左 Alt",en: "Left Alt"}.to_string(),
+        RAlt: "ralt", t! {zh: "右 Alt",en: "Right Alt"}.to_string(),
+        LCtrl: "lctrl", t! {zh: "左 Ctrl",en: "Left Ctrl"}.to_string(),
+        RCtrl: "rctrl", t! {zh: "右 Ctrl",en: "Right Ctrl"}.to_string(),
+        LSuper: "lsuper", t! {zh: "左 Super",en: "Left Super"}.to_string(),
+        RSuper: "rsuper", t! {zh: "右 Super",en: "Right Super"}.to_string(),
+    }
+
+    pub enum MouseBind{
+        #[default]
+        Forward: "+", t! {zh: "转发",en: "Forward"}.to_string(),
+        Ignore: "-", t! {zh: "忽略",en: "Ignore"}.to_string(),
+        Back: "b", t! {zh: "返回",en: "Back"}.to_string(),
+        Home: "h", t! {zh: "主页",en: "Home"}.to_string(),
+        AppSwitch: "s", t! {zh: "切换应用",en: "App switch"}.to_string(),
+        Notification: "n", t! {zh: "展开通知栏",en: "Notification panel"}.to_string(),
+    }
+
+    pub enum DisplayImePolicy{
+        Local: "local", t! {zh: "当前显示器",en: "Local"}.to_string(),
+        #[default]
+        Fallback: "fallback", t! {zh: "默认显示器",en: "Fallback"}.to_string(),
+        Hide: "hide", t! {zh: "隐藏",en: "Hide"}.to_string(),
+    }
+
+    pub enum AppNameType{
+        #[default]
+        PackageName: "package_name", t! {zh: "包名",en: "Package name"}.to_string(),
+        AppName: "app_name", t! {zh: "应用名",en: "App name"}.to_string(),
+    }
+
+    pub enum HookFailure{
+        #[default]
+        Abort: "abort", t! {zh: "中止启动",en: "Abort launch"}.to_string(),
+        Warn: "warn", t! {zh: "仅警告",en: "Warn only"}.to_string(),
+    }
+}
+
+impl VideoSource {
+    pub fn min_sdk(&self) -> u32 {
+        match self {
+            VideoSource::Camera => 31,
+            _ => 0,
+        }
+    }
+
+    /// Sources the device can provide, every source if the SDK is unknown.
+    pub fn available(sdk: Option<u32>) -> Vec<Self> {
+        Self::states()
+            .into_iter()
+            .filter(|s| sdk.is_none_or(|sdk| s.min_sdk() <= sdk))
+            .collect()
+    }
+}
+
+impl AudioSource {
+    pub fn min_sdk(&self) -> u32 {
+        match self {
+            AudioSource::No => 0,
+            AudioSource::Playback => 33,
+            _ => 30,
+        }
+    }
+
+    /// Sources the device can provide, every source if the SDK is unknown.
+    pub fn available(sdk: Option<u32>) -> Vec<Self> {
+        Self::states()
+            .into_iter()
+            .filter(|s| sdk.is_none_or(|sdk| s.min_sdk() <= sdk))
+            .collect()
+    }
+}
+
+impl RenderDriver {
+    pub fn available() -> Vec<Self> {
+        Self::states()
+            .into_iter()
+            .filter(|d| {
+                (*d != RenderDriver::Metal || cfg!(target_os = "macos"))
+                    && (*d != RenderDriver::Direct3d || cfg!(target_os = "windows"))
+            })
+            .collect()
+    }
+}
+
+impl MouseBind {
+    /// What scrcpy binds when `--mouse-bind` is omitted.
+    pub fn defaults(mouse: Mouse) -> [Self; 8] {
+        let bindings = match mouse {
+            Mouse::Sdk => "bhsn:++++",
+            _ => "++++:bhsn",
+        };
+        Self::from_config_bindings(&Some(bindings.to_string())).unwrap()
+    }
+
+    pub fn from_config_bindings(s: &Option<String>) -> Option<[Self; 8]> {
+        let s = s.as_ref()?.trim();
+        if s.is_empty() {
+            return None;
+        }
+        let mut bindings = [MouseBind::Forward; 8];
+        let (primary, shift) = s.split_once(':').unwrap_or((s, "++++"));
+        for (i, c) in primary.chars().chain(shift.chars()).take(8).enumerate() {
+            bindings[i] = MouseBind::from_config_str(&Some(c.to_string()));
+        }
+        Some(bindings)
+    }
+
+    pub fn to_config_bindings(bindings: &[Self; 8]) -> String {
+        let s: Vec<String> = bindings.iter().map(|b| b.to_config_string()).collect();
+        format!("{}:{}", s[..4].concat(), s[4..].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::arbitrary;
+
+    #[test]
+    fn config_item_survives_toml() {
+        for seed in 0..500 {
+            let config = arbitrary::config(&mut fastrand::Rng::with_seed(seed));
+            let toml = toml::to_string(&config.to_raw()).unwrap();
+            let raw: ConfigItemRaw = toml::from_str(&toml).unwrap();
+            assert_eq!(raw.to_config(), config, "seed {seed}:\n{toml}");
+        }
+    }
+
+    #[test]
+    fn detected_adb_is_not_saved() {
+        let mut config = ConfigRaw::default().to_config();
+        config.detected_adb = Some("/opt/platform-tools/adb".into());
+        assert_eq!(config.adb(), Some("/opt/platform-tools/adb"));
+        assert_eq!(config.to_raw().adb, None);
+        config.adb = Some("/usr/bin/adb".into());
+        assert_eq!(config.adb(), Some("/usr/bin/adb"));
+        assert_eq!(config.to_raw().adb.as_deref(), Some("/usr/bin/adb"));
+    }
+
+    #[test]
+    fn glob_patterns() {
+        let cases = [
+            ("SM-T*", "SM-T870", true),
+            ("sm-t*", "SM-T870", true),
+            ("SM-T*", "SM-S918B", false),
+            ("Pixel ?", "Pixel 7", true),
+            ("Pixel ?", "Pixel 7a", false),
+            ("Pixel ?*", "Pixel 7 Pro", true),
+            ("*Pro", "Pixel 7 Pro", true),
+            ("*7*", "Pixel 7 Pro", true),
+            ("*", "", true),
+            ("?", "", false),
+            ("a*b*c", "aXXbYYc", true),
+            ("a*b*c", "aXXbYY", false),
+            ("Pixel 7", "Pixel 7 Pro", false),
+        ];
+        for (pattern, model, expected) in cases {
+            assert_eq!(glob_match(pattern, model), expected, "{pattern} ~ {model}");
+        }
+    }
+
+    #[test]
+    fn device_rules() {
+        let rule = |serial: Option<&str>, model: Option<&str>, min_sdk| DeviceRule {
+            serial: serial.map(str::to_string),
+            model: model.map(str::to_string),
+            min_sdk,
+            profile: "p".into(),
+        };
+        let cases = [
+            (rule(None, None, None), true),
+            (rule(Some("R58M1"), None, None), true),
+            (rule(Some("r58m1"), None, None), false),
+            (rule(Some("R58M2"), None, None), false),
+            (rule(None, Some("sm-t*"), None), true),
+            (rule(None, Some("SM-S*"), None), false),
+            (rule(None, None, Some(33)), true),
+            (rule(None, None, Some(34)), false),
+            (rule(Some("R58M1"), Some("SM-T870"), Some(30)), true),
+            (rule(Some("R58M1"), Some("SM-T870"), Some(34)), false),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(rule.matches("R58M1", "SM-T870", 33), expected, "{rule}");
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let raw: ConfigRaw = toml::from_str(
+            r#"
+            version = 1
+            [saved.tablet]
+            serial = "from tablet"
+            [saved.modern]
+            serial = "from modern"
+            [saved.fallback]
+            serial = "from fallback"
+
+            [[rules]]
+            model = "SM-T*"
+            min_sdk = 30
+            profile = "tablet"
+            [[rules]]
+            min_sdk = 34
+            profile = "modern"
+            [[rules]]
+            model = "Pixel*"
+            profile = "deleted"
+            [[rules]]
+            profile = "fallback"
+            "#,
+        )
+        .unwrap();
+        let config = raw.to_config();
+        let cases = [
+            ("sm-t870", 33, "tablet"),
+            ("SM-T870", 29, "fallback"),
+            ("SM-T870", 34, "tablet"),
+            ("SM-S918B", 34, "modern"),
+            ("Pixel 8", 35, "modern"),
+            // a rule naming a missing profile is skipped
+            ("Pixel 6", 33, "fallback"),
+        ];
+        for (model, sdk, expected) in cases {
+            let (name, profile) = config.profile_for_device("serial", model, sdk).unwrap();
+            assert_eq!(
+                (name, profile.serial.as_str()),
+                (expected, &*format!("from {expected}"))
+            );
+        }
+
+        let config = ConfigRaw {
+            rules: raw.rules.map(|rules| rules[..1].to_vec()),
+            ..raw
+        }
+        .to_config();
+        assert!(config.profile_for_device("serial", "Pixel 6", 33).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Common install locations checked after `PATH`.
+fn common_dirs() -> Vec<PathBuf> {
+    let home = home::home_dir();
+    let mut dirs = vec![];
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(local) = env::var("LOCALAPPDATA") {
+            dirs.push(
+                Path::new(&local)
+                    .join("Microsoft")
+                    .join("WinGet")
+                    .join("Links"),
+            );
+        }
+        if let Some(home) = &home {
+            dirs.push(home.join("scoop").join("shims"));
+        }
+        dirs.push(PathBuf::from(r"C:\ProgramData\chocolatey\bin"));
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(home) = &home {
+            dirs.push(home.join(".local/bin"));
+            dirs.push(home.join(".local/share/flatpak/exports/bin"));
+            dirs.push(home.join(".linuxbrew/bin"));
+        }
+        dirs.extend(
+            [
+                "/usr/local/bin",
+                "/usr/bin",
+                "/snap/bin",
+                "/var/lib/flatpak/exports/bin",
+                "/opt/homebrew/bin",
+                "/home/linuxbrew/.linuxbrew/bin",
+                "/opt/local/bin",
+            ]
+            .map(PathBuf::from),
+        );
+    }
+    dirs
+}
+
+/// `first`, then `PATH`, then [`common_dirs`].
+fn search_dirs(first: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = first.map(Path::to_path_buf).into_iter().collect();
+    if let Some(path) = env::var_os("PATH") {
+        dirs.extend(env::split_paths(&path));
+    }
+    dirs.extend(common_dirs());
+    dirs
+}
+
+/// Whether `path` is a file the current user may execute.
+pub fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+fn find_all(first: Option<&Path>, files: &[&str]) -> Vec<String> {
+    let dirs = search_dirs(first);
+    let mut found: Vec<(PathBuf, String)> = vec![];
+    for file in files {
+        for dir in &dirs {
+            let full_path = dir.join(file);
+            if !is_executable(&full_path) {
+                continue;
+            }
+            // the same install is often reachable through several dirs
+            let canonical = fs::canonicalize(&full_path).unwrap_or(full_path.clone());
+            if found.iter().all(|(c, _)| *c != canonical) {
+                found.push((canonical, full_path.to_string_lossy().to_string()));
+            }
+        }
+    }
+    found.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Every scrcpy found next to the wrapper, in `PATH` and in common install
+/// locations, best candidate first.
+pub fn find_scrcpy_installs() -> Vec<String> {
+    let base = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    #[cfg(target_os = "windows")]
+    let files = ["scrcpy.exe", "scrcpy.cmd", "scrcpy.bat"];
+    #[cfg(not(target_os = "windows"))]
+    let files = ["scrcpy", "scrcpy.sh"];
+    find_all(base.as_deref(), &files)
+}
+
+/// Looks for adb next to the scrcpy executable, then in `PATH` and in
+/// common install locations.
+pub fn find_adb(executable: &Option<String>) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let file = "adb.exe";
+    #[cfg(not(target_os = "windows"))]
+    let file = "adb";
+    let dir = executable.as_ref().and_then(|e| Path::new(e).parent());
+    find_all(dir, &[file]).into_iter().next()
+}
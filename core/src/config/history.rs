@@ -0,0 +1,197 @@
+use crate::config::{history_path, ConfigItemRaw};
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sessions kept in the history file; older ones are dropped.
+const MAX_SESSIONS: usize = 200;
+/// Log lines kept for a failed session.
+pub const LOG_TAIL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Start time, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub profile: Option<String>,
+    pub serial: String,
+    pub argv: Vec<String>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    /// Duration in seconds.
+    pub duration: u64,
+    pub log_tail: Vec<String>,
+    pub config: Option<ConfigItemRaw>,
+    /// Present when the session ran with the FPS monitor.
+    pub perf: Option<PerfSummary>,
+}
+
+/// FPS statistics of a session, from the `--print-fps` output. The FPS
+/// figures are absent when scrcpy printed no FPS line, e.g. only buffering
+/// warnings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerfSummary {
+    pub min_fps: Option<u32>,
+    pub avg_fps: Option<f32>,
+    pub max_fps: Option<u32>,
+    pub skipped_frames: u64,
+    pub buffering_events: u32,
+    /// FPS over the session, see [`CHART_POINTS`](crate::util::CHART_POINTS).
+    pub chart: Vec<u32>,
+}
+
+impl Display for PerfSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.min_fps, self.avg_fps, self.max_fps) {
+            (Some(min), Some(avg), Some(max)) => {
+                write!(f, "min {min} · avg {avg:.1} · max {max} fps")?
+            }
+            _ => write!(f, "no fps")?,
+        }
+        write!(
+            f,
+            " · {} skipped · {} buffering",
+            self.skipped_frames, self.buffering_events
+        )
+    }
+}
+
+impl SessionRecord {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// `YYYY-MM-DD HH:MM` in UTC.
+    pub fn started_at(&self) -> String {
+        let days = (self.timestamp / 86400) as i64;
+        let minutes = self.timestamp % 86400 / 60;
+        // days since the epoch to a civil date, see
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!(
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+            minutes / 60,
+            minutes % 60
+        )
+    }
+
+    /// `exit 1`, `signal 9` or `unknown`.
+    pub fn outcome(&self) -> String {
+        match (self.exit_code, self.signal) {
+            (Some(code), _) => format!("exit {code}"),
+            (None, Some(signal)) => format!("signal {signal}"),
+            (None, None) => "unknown".to_string(),
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    pub sessions: Vec<SessionRecord>,
+}
+
+impl History {
+    /// The recorded sessions; empty if there is no history file yet.
+    pub fn load() -> Result<Self> {
+        match fs::read_to_string(history_path()) {
+            Ok(s) => Ok(toml::from_str(&s)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Adds a session to the history file. The file stays locked from read
+    /// to write, as sessions started over the control API end concurrently,
+    /// and is left alone if it can't be parsed.
+    pub fn append(record: SessionRecord) -> Result<()> {
+        let path = history_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        file.lock()?;
+        let mut s = String::new();
+        file.read_to_string(&mut s)?;
+        let mut history: Self = toml::from_str(&s)?;
+        history.sessions.push(record);
+        let excess = history.sessions.len().saturating_sub(MAX_SESSIONS);
+        history.sessions.drain(..excess);
+        let s = toml::to_string(&history)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        file.write_all(s.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started_at(timestamp: u64) -> String {
+        SessionRecord {
+            timestamp,
+            ..Default::default()
+        }
+        .started_at()
+    }
+
+    #[test]
+    fn formats_the_epoch() {
+        assert_eq!(started_at(0), "1970-01-01 00:00");
+        assert_eq!(started_at(59), "1970-01-01 00:00");
+        assert_eq!(started_at(86340), "1970-01-01 23:59");
+        assert_eq!(started_at(86400), "1970-01-02 00:00");
+    }
+
+    #[test]
+    fn formats_leap_days() {
+        for (timestamp, date) in [
+            (68212800, "1972-02-29 12:00"),
+            (951782400, "2000-02-29 00:00"),
+            (1709251140, "2024-02-29 23:59"),
+            (1709251200, "2024-03-01 00:00"),
+            (1677542400, "2023-02-28 00:00"),
+            (1677628800, "2023-03-01 00:00"),
+            (4107456000, "2100-02-28 00:00"),
+            (4107542400, "2100-03-01 00:00"),
+        ] {
+            assert_eq!(started_at(timestamp), date, "{timestamp}");
+        }
+    }
+
+    #[test]
+    fn formats_month_and_year_rollovers() {
+        for (timestamp, date) in [
+            (946684740, "1999-12-31 23:59"),
+            (946684800, "2000-01-01 00:00"),
+            (1706745540, "2024-01-31 23:59"),
+            (1706745600, "2024-02-01 00:00"),
+        ] {
+            assert_eq!(started_at(timestamp), date, "{timestamp}");
+        }
+    }
+}
@@ -0,0 +1,16 @@
+#[cfg(test)]
+pub mod arbitrary;
+mod bit_rate;
+mod conf;
+mod discovery;
+mod history;
+mod options;
+mod presets;
+mod units;
+pub use bit_rate::*;
+pub use conf::*;
+pub use discovery::*;
+pub use history::*;
+pub use options::*;
+pub use presets::*;
+pub use units::*;
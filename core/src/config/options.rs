@@ -0,0 +1,556 @@
+use crate::config::{
+    AudioCodec, AudioSource, BitRate, ButtonState, Camera, ConfigEnum, ConfigItem, ConnectMethod,
+    Dimension, DisplayImePolicy, FieldValue, Gamepad, Keyboard, Millis, Mouse, Seconds, UnitError,
+    VideoSource,
+};
+use crate::t;
+use crate::util::quote;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The value of a registered option, as exchanged with the generic widgets.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionValue {
+    Switch(bool),
+    Text(String),
+    Number(Option<u32>),
+    BitRate(Option<BitRate>),
+    Decimal(Option<f32>),
+    /// A variant of an enum, by its name in `scrcpy-wrapper.toml`.
+    Choice(String),
+}
+
+/// An entry in the pick list of an enum option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub value: String,
+    pub label: String,
+}
+
+impl Display for Choice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// Field types usable in [`scrcpy_options!`].
+pub trait OptionType: Sized + Clone + PartialEq {
+    /// How the value is stored in `scrcpy-wrapper.toml`.
+    type Raw: Clone + Serialize + for<'de> Deserialize<'de>;
+
+    fn to_raw(&self) -> Option<Self::Raw>;
+    fn from_raw(raw: Self::Raw) -> Self;
+    /// The argument for this value, `None` while it matches scrcpy's default.
+    fn to_arg(&self, flag: &str, default: &Self) -> Option<String>;
+    /// The value set by `token`, if `token` is this option.
+    fn from_arg(token: &str, flag: &str, default: &Self) -> Option<Self>;
+    fn to_value(&self) -> OptionValue;
+    fn from_value(value: OptionValue) -> Option<Self>;
+
+    /// The value of the option's text field holding `text`.
+    fn parse_value(text: &str) -> Result<OptionValue, UnitError> {
+        Ok(OptionValue::Text(text.to_string()))
+    }
+
+    /// The values offered in the option's pick list, if it has one.
+    fn choices() -> Vec<Choice> {
+        vec![]
+    }
+}
+
+/// A switch is passed as `flag` when it differs from its default, so `flag`
+/// is `--no-...` for switches that are on by default.
+impl OptionType for bool {
+    type Raw = bool;
+
+    fn to_raw(&self) -> Option<bool> {
+        Some(*self)
+    }
+
+    fn from_raw(raw: bool) -> Self {
+        raw
+    }
+
+    fn to_arg(&self, flag: &str, default: &Self) -> Option<String> {
+        (self != default).then(|| flag.to_string())
+    }
+
+    fn from_arg(token: &str, flag: &str, default: &Self) -> Option<Self> {
+        (token == flag).then_some(!default)
+    }
+
+    fn to_value(&self) -> OptionValue {
+        OptionValue::Switch(*self)
+    }
+
+    fn from_value(value: OptionValue) -> Option<Self> {
+        match value {
+            OptionValue::Switch(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl OptionType for String {
+    type Raw = String;
+
+    fn to_raw(&self) -> Option<String> {
+        Some(self.clone())
+    }
+
+    fn from_raw(raw: String) -> Self {
+        raw
+    }
+
+    fn to_arg(&self, flag: &str, _: &Self) -> Option<String> {
+        let value = self.trim();
+        (!value.is_empty()).then(|| format!("{flag}={}", quote(value)))
+    }
+
+    fn from_arg(token: &str, flag: &str, _: &Self) -> Option<Self> {
+        token
+            .strip_prefix(flag)?
+            .strip_prefix('=')
+            .map(|v| v.to_string())
+    }
+
+    fn to_value(&self) -> OptionValue {
+        OptionValue::Text(self.clone())
+    }
+
+    fn from_value(value: OptionValue) -> Option<Self> {
+        match value {
+            OptionValue::Text(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! number_option_type {
+    ($($ty:ty),*) => {$(
+        impl OptionType for Option<$ty> {
+            type Raw = $ty;
+
+            fn to_raw(&self) -> Option<$ty> {
+                *self
+            }
+
+            fn from_raw(raw: $ty) -> Self {
+                Some(raw)
+            }
+
+            fn to_arg(&self, flag: &str, _: &Self) -> Option<String> {
+                self.map(|v| format!("{flag}={v}"))
+            }
+
+            fn from_arg(token: &str, flag: &str, _: &Self) -> Option<Self> {
+                let value = token.strip_prefix(flag)?.strip_prefix('=')?;
+                value.parse().ok().map(Some)
+            }
+
+            fn to_value(&self) -> OptionValue {
+                OptionValue::Number(self.map(u32::from))
+            }
+
+            fn from_value(value: OptionValue) -> Option<Self> {
+                match value {
+                    OptionValue::Number(None) => Some(None),
+                    OptionValue::Number(Some(v)) => <$ty>::try_from(v).ok().map(Some),
+                    _ => None,
+                }
+            }
+
+            fn parse_value(text: &str) -> Result<OptionValue, UnitError> {
+                <$ty>::parse_optional(text).map(|v| OptionValue::Number(v.map(u32::from)))
+            }
+        }
+    )*};
+}
+
+number_option_type!(u16, u32, Millis, Seconds, Dimension);
+
+impl OptionType for Option<f32> {
+    type Raw = f32;
+
+    fn to_raw(&self) -> Option<f32> {
+        *self
+    }
+
+    fn from_raw(raw: f32) -> Self {
+        Some(raw)
+    }
+
+    fn to_arg(&self, flag: &str, _: &Self) -> Option<String> {
+        self.map(|v| format!("{flag}={v}"))
+    }
+
+    fn from_arg(token: &str, flag: &str, _: &Self) -> Option<Self> {
+        let value = token.strip_prefix(flag)?.strip_prefix('=')?;
+        f32::parse_field(value).ok().map(Some)
+    }
+
+    fn to_value(&self) -> OptionValue {
+        OptionValue::Decimal(*self)
+    }
+
+    fn from_value(value: OptionValue) -> Option<Self> {
+        match value {
+            OptionValue::Decimal(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn parse_value(text: &str) -> Result<OptionValue, UnitError> {
+        f32::parse_optional(text).map(OptionValue::Decimal)
+    }
+}
+
+impl OptionType for Option<BitRate> {
+    type Raw = BitRate;
+
+    fn to_raw(&self) -> Option<BitRate> {
+        *self
+    }
+
+    fn from_raw(raw: BitRate) -> Self {
+        Some(raw)
+    }
+
+    fn to_arg(&self, flag: &str, _: &Self) -> Option<String> {
+        self.map(|v| format!("{flag}={v}"))
+    }
+
+    fn from_arg(token: &str, flag: &str, _: &Self) -> Option<Self> {
+        let value = token.strip_prefix(flag)?.strip_prefix('=')?;
+        value.parse().ok().map(Some)
+    }
+
+    fn to_value(&self) -> OptionValue {
+        OptionValue::BitRate(*self)
+    }
+
+    fn from_value(value: OptionValue) -> Option<Self> {
+        match value {
+            OptionValue::BitRate(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn parse_value(text: &str) -> Result<OptionValue, UnitError> {
+        BitRate::parse_optional(text).map(OptionValue::BitRate)
+    }
+}
+
+/// An enum is passed as `flag=name` when it differs from its default, with
+/// the name it has in `scrcpy-wrapper.toml`.
+macro_rules! enum_option_type {
+    ($($ty:ty),*) => {$(
+        impl OptionType for $ty {
+            type Raw = String;
+
+            fn to_raw(&self) -> Option<String> {
+                Some(self.to_config_string())
+            }
+
+            fn from_raw(raw: String) -> Self {
+                Self::from_config_str(&Some(raw))
+            }
+
+            fn to_arg(&self, flag: &str, default: &Self) -> Option<String> {
+                (self != default).then(|| format!("{flag}={}", self.to_config_string()))
+            }
+
+            fn from_arg(token: &str, flag: &str, _: &Self) -> Option<Self> {
+                let value = token.strip_prefix(flag)?.strip_prefix('=')?;
+                Self::states().into_iter().find(|s| s.to_config_string() == value)
+            }
+
+            fn to_value(&self) -> OptionValue {
+                OptionValue::Choice(self.to_config_string())
+            }
+
+            fn from_value(value: OptionValue) -> Option<Self> {
+                match value {
+                    OptionValue::Choice(v) => {
+                        Self::states().into_iter().find(|s| s.to_config_string() == v)
+                    }
+                    _ => None,
+                }
+            }
+
+            fn choices() -> Vec<Choice> {
+                Self::states()
+                    .into_iter()
+                    .map(|s| Choice {
+                        value: s.to_config_string(),
+                        label: s.to_string(),
+                    })
+                    .collect()
+            }
+        }
+    )*};
+}
+
+enum_option_type!(Camera, Gamepad, DisplayImePolicy);
+
+pub struct OptionSpec {
+    pub key: OptionKey,
+    pub flag: &'static str,
+    /// Whether the option applies to `config` at all.
+    pub applies: fn(&ConfigItem) -> bool,
+    pub label: fn() -> String,
+}
+
+impl OptionKey {
+    pub fn spec(self) -> &'static OptionSpec {
+        OPTIONS.iter().find(|spec| spec.key == self).unwrap()
+    }
+}
+
+/// Declares the plain scrcpy options: a switch, a text, a number, a bit
+/// rate or an enum, each mapped to a single flag. Options needing more than
+/// that (flags built from several fields, choices depending on the
+/// platform) are written by hand in `ConfigItem` and `build_raw_args`. A
+/// plain option tied to another field, such as the synced audio buffer,
+/// is still declared here; only its widget is written by hand.
+macro_rules! scrcpy_options {
+    ($(
+        $key:ident $field:ident: $ty:ty = $default:expr,
+            $flag:literal if $applies:expr,
+            {en: $en:literal, zh: $zh:literal};
+    )*) => {
+        /// Flattened into `ConfigItemRaw`, so unset fields are left out.
+        #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+        pub struct ScrcpyOptionsRaw {
+            $(
+                #[serde(
+                    default,
+                    deserialize_with = "crate::config::lenient",
+                    skip_serializing_if = "Option::is_none"
+                )]
+                pub $field: Option<<$ty as OptionType>::Raw>,
+            )*
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct ScrcpyOptions {
+            $(pub $field: $ty,)*
+        }
+
+        impl Default for ScrcpyOptions {
+            fn default() -> Self {
+                Self {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl ScrcpyOptionsRaw {
+            pub fn to_options(&self) -> ScrcpyOptions {
+                let default = ScrcpyOptions::default();
+                ScrcpyOptions {
+                    $($field: match &self.$field {
+                        Some(raw) => OptionType::from_raw(raw.clone()),
+                        None => default.$field,
+                    },)*
+                }
+            }
+        }
+
+        impl ScrcpyOptions {
+            pub fn to_raw(&self) -> ScrcpyOptionsRaw {
+                ScrcpyOptionsRaw {
+                    $($field: self.$field.to_raw(),)*
+                }
+            }
+
+            pub fn get(&self, key: OptionKey) -> OptionValue {
+                match key {
+                    $(OptionKey::$key => self.$field.to_value(),)*
+                }
+            }
+
+            pub fn set(&mut self, key: OptionKey, value: OptionValue) {
+                match key {
+                    $(OptionKey::$key => {
+                        if let Some(value) = OptionType::from_value(value) {
+                            self.$field = value;
+                        }
+                    })*
+                }
+            }
+
+            /// The entries of the pick list of `key`, empty unless it is an enum.
+            pub fn choices(key: OptionKey) -> Vec<Choice> {
+                match key {
+                    $(OptionKey::$key => <$ty as OptionType>::choices(),)*
+                }
+            }
+
+            /// The value `text` typed into the field of `key` stands for.
+            pub fn parse(key: OptionKey, text: &str) -> Result<OptionValue, UnitError> {
+                match key {
+                    $(OptionKey::$key => <$ty as OptionType>::parse_value(text),)*
+                }
+            }
+
+            /// The argument for `key`, ignoring whether it applies.
+            pub fn arg(&self, key: OptionKey) -> Option<String> {
+                let default = Self::default();
+                match key {
+                    $(OptionKey::$key => self.$field.to_arg($flag, &default.$field),)*
+                }
+            }
+
+            /// Sets the option `token` stands for, returning whether it is one.
+            pub fn apply_arg(&mut self, token: &str) -> bool {
+                let default = Self::default();
+                $(
+                    if let Some(value) = <$ty as OptionType>::from_arg(token, $flag, &default.$field) {
+                        self.$field = value;
+                        return true;
+                    }
+                )*
+                false
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum OptionKey {
+            $($key,)*
+        }
+
+        pub const OPTIONS: &[OptionSpec] = &[
+            $(OptionSpec {
+                key: OptionKey::$key,
+                flag: $flag,
+                applies: $applies,
+                label: || t! {en: $en, zh: $zh}.to_string(),
+            },)*
+        ];
+    };
+}
+
+scrcpy_options! {
+    TunnelHost tunnel_host: String = String::new(),
+        "--tunnel-host" if |c| c.connect_method != ConnectMethod::Otg,
+        {en: "Tunnel host: ", zh: "隧道主机："};
+    TunnelPort tunnel_port: Option<u16> = None,
+        "--tunnel-port" if |c| c.connect_method != ConnectMethod::Otg,
+        {en: "port: ", zh: "端口："};
+    ForceAdbForward force_adb_forward: bool = false,
+        "--force-adb-forward" if |c| c.connect_method != ConnectMethod::Otg,
+        {en: "Force adb forward", zh: "强制使用 adb forward"};
+    KillAdbOnClose kill_adb_on_close: bool = false,
+        "--kill-adb-on-close" if |c| c.connect_method != ConnectMethod::Otg,
+        {en: "Kill adb on close", zh: "关闭时结束 adb"};
+    Cleanup cleanup: bool = true,
+        "--no-cleanup" if |c| c.connect_method != ConnectMethod::Otg,
+        {en: "Clean up device on exit", zh: "退出时清理设备"};
+    DownsizeOnError downsize_on_error: bool = true,
+        "--no-downsize-on-error" if |c| c.connect_method != ConnectMethod::Otg,
+        {en: "Downsize on encoder error", zh: "编码出错时降低分辨率"};
+    VideoSize video_size: Option<Dimension> = None,
+        "--max-size" if |c| c.video_source != VideoSource::No,
+        {en: "Size: ", zh: "分辨率："};
+    Camera camera: Camera = Camera::default(),
+        "--camera-facing" if |c| c.video_source == VideoSource::Camera,
+        {en: "Camera: ", zh: "摄像头："};
+    Angle angle: Option<f32> = None,
+        "--angle" if |c| c.video_source != VideoSource::No,
+        {en: "Rotation angle: ", zh: "旋转角度："};
+    Mipmaps mipmaps: bool = true,
+        "--no-mipmaps" if |c| c.video_source != VideoSource::No && c.video_playback,
+        {en: "Mipmaps", zh: "多级纹理"};
+    AudioDup audio_dup: bool = false,
+        "--audio-dup" if |c| c.audio_source == AudioSource::Playback,
+        {en: "duplicate audio", zh: "复制音频"};
+    VideoBitRate video_bit_rate: Option<BitRate> = None,
+        "--video-bit-rate" if |c| c.video_source != VideoSource::No,
+        {en: "Video bit rate: ", zh: "视频码率："};
+    AudioBitRate audio_bit_rate: Option<BitRate> = None,
+        "--audio-bit-rate" if |c| c.audio_source != AudioSource::No && c.audio_codec != AudioCodec::Raw,
+        {en: "Audio bit rate: ", zh: "音频码率："};
+    AudioBuffer audio_buffer: Option<Millis> = None,
+        "--audio-buffer" if |c| c.audio_source != AudioSource::No,
+        {en: "audio", zh: "音频"};
+    PrintFps print_fps: bool = false,
+        "--print-fps" if |c| c.video_source != VideoSource::No && c.video_playback,
+        {en: "Monitor FPS", zh: "监测帧率"};
+    Gamepad gamepad: Gamepad = Gamepad::default(),
+        "--gamepad" if |_| true,
+        {en: "Gamepad input modes: ", zh: "游戏手柄输入模式："};
+    Control control: bool = true,
+        "--no-control" if |_| true,
+        {en: "Enable control", zh: "启用控制"};
+    KeyRepeat key_repeat: bool = true,
+        "--no-key-repeat" if |c| c.options.control && c.keyboard == Keyboard::Sdk,
+        {en: "Key repeat", zh: "按键重复"};
+    LegacyPaste legacy_paste: bool = false,
+        "--legacy-paste" if |c| c.options.control && c.keyboard == Keyboard::Sdk,
+        {en: "Legacy paste (inject clipboard as key events on Ctrl+v)", zh: "传统粘贴（Ctrl+v 时以按键事件注入剪贴板）"};
+    ClipboardAutosync clipboard_autosync: bool = true,
+        "--no-clipboard-autosync" if |c| c.options.control && c.connect_method != ConnectMethod::Otg,
+        {en: "Synchronize clipboard automatically", zh: "自动同步剪贴板"};
+    MouseHover mouse_hover: bool = true,
+        "--no-mouse-hover" if |c| c.options.control && c.mouse == Mouse::Sdk,
+        {en: "Hover", zh: "悬停"};
+    Record record: String = String::new(),
+        "--record" if |_| true,
+        {en: "Record file: ", zh: "录制文件："};
+    V4l2 v4l2: String = String::new(),
+        "--v4l2-sink" if |_| true,
+        {en: "V4L2 device: ", zh: "V4L2 设备："};
+    V4l2Buffer v4l2_buffer: Option<Millis> = None,
+        "--v4l2-buffer" if |c| !c.options.v4l2.trim().is_empty(),
+        {en: "buffer (ms): ", zh: "缓冲（毫秒）："};
+    DisplayImePolicy display_ime_policy: DisplayImePolicy = DisplayImePolicy::default(),
+        "--display-ime-policy" if |c| c.virtual_display,
+        {en: "IME policy: ", zh: "输入法策略："};
+    DestroyAppOnClose destroy_app_on_close: bool = true,
+        "--no-vd-destroy-content" if |c| c.virtual_display,
+        {en: "Destroy app on close", zh: "关闭时销毁应用"};
+    TimeLimit time_limit: Option<Seconds> = None,
+        "--time-limit" if |_| true,
+        {en: "Time limit (s): ", zh: "时间限制（秒）："};
+    StayAwake stay_awake: bool = false,
+        "--stay-awake" if |_| true,
+        {en: "Stay awake (device)", zh: "保持唤醒（设备）"};
+    DisableWindow disable_window: bool = false,
+        "--no-window" if |_| true,
+        {en: "Disable window", zh: "禁用窗口"};
+    Borderless borderless: bool = false,
+        "--window-borderless" if |_| true,
+        {en: "Borderless", zh: "无边框"};
+    AlwaysOnTop always_on_top: bool = false,
+        "--always-on-top" if |_| true,
+        {en: "Always on top", zh: "窗口置顶"};
+    Fullscreen fullscreen: bool = false,
+        "--fullscreen" if |_| true,
+        {en: "Fullscreen", zh: "全屏"};
+    DisableScreensaver disable_screensaver: bool = false,
+        "--no-screensaver" if |_| true,
+        {en: "Disable screensaver (PC)", zh: "禁用屏保（电脑）"};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::arbitrary;
+
+    #[test]
+    fn args_parse_back() {
+        for seed in 0..500 {
+            let options = arbitrary::config(&mut fastrand::Rng::with_seed(seed)).options;
+            let mut parsed = ScrcpyOptions::default();
+            for spec in OPTIONS {
+                let Some(arg) = options.arg(spec.key) else {
+                    continue;
+                };
+                let token = shell_words::split(&arg).unwrap().join(" ");
+                assert!(parsed.apply_arg(&token), "seed {seed}: {arg}");
+                assert_eq!(parsed.arg(spec.key), Some(arg), "seed {seed}");
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::config::{ConfigItem, ConfigItemRaw};
+use crate::{t, Result};
+use std::fmt::{self, Display, Formatter};
+
+/// A curated set of settings shipped with the wrapper. Presets are partial:
+/// applying one only touches the fields it lists, in the format of
+/// `scrcpy-wrapper.toml`.
+#[derive(Debug)]
+pub struct Preset {
+    pub key: &'static str,
+    name: fn() -> String,
+    description: fn() -> String,
+    overlay: &'static str,
+}
+
+impl Preset {
+    pub fn find(key: &str) -> Option<&'static Preset> {
+        PRESETS.iter().find(|p| p.key == key)
+    }
+
+    pub fn description(&self) -> String {
+        (self.description)()
+    }
+
+    pub fn overlay(&self) -> Result<ConfigItemRaw> {
+        Ok(toml::from_str(self.overlay)?)
+    }
+
+    /// `config` with the preset's settings on top.
+    pub fn apply(&self, config: &ConfigItem) -> Result<ConfigItem> {
+        Ok(config.to_raw().overlay(&self.overlay()?)?.to_config())
+    }
+}
+
+impl PartialEq for Preset {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", (self.name)())
+    }
+}
+
+impl ConfigItemRaw {
+    /// `self` with every field that is set in `overlay` replaced.
+    pub fn overlay(&self, overlay: &ConfigItemRaw) -> Result<ConfigItemRaw> {
+        let mut table = toml::Table::try_from(self)?;
+        table.extend(toml::Table::try_from(overlay)?);
+        Ok(table.try_into()?)
+    }
+}
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        key: "low-latency",
+        name: || t! {en: "Low latency", zh: "低延迟"}.to_string(),
+        description: || {
+            t! {
+                en: "No video buffering, h264 at a modest bit rate.",
+                zh: "不缓冲视频，h264 编码，较低码率。"
+            }
+            .to_string()
+        },
+        overlay: r#"
+            video_codec = "h264"
+            video_bit_rate = "4M"
+            buffer_flag = "video"
+            video_buffer = 0
+            # editing the zero video buffer shouldn't zero the audio one too
+            buffer_sync = false
+        "#,
+    },
+    Preset {
+        key: "quality",
+        name: || t! {en: "High quality", zh: "高画质"}.to_string(),
+        description: || {
+            t! {
+                en: "h265 at 16 Mbps.",
+                zh: "h265 编码，16 Mbps。"
+            }
+            .to_string()
+        },
+        overlay: r#"
+            video_codec = "h265"
+            video_codec_fallback = "h264"
+            video_bit_rate = "16M"
+        "#,
+    },
+    Preset {
+        key: "gaming",
+        name: || t! {en: "Gaming", zh: "游戏"}.to_string(),
+        description: || {
+            t! {
+                en: "UHID keyboard, mouse and gamepad at up to 120 fps.",
+                zh: "UHID 键盘、鼠标和手柄，最高 120 帧。"
+            }
+            .to_string()
+        },
+        overlay: r#"
+            control = true
+            keyboard = "uhid"
+            mouse = "uhid"
+            gamepad = "uhid"
+            fps = 120
+        "#,
+    },
+    Preset {
+        key: "recording",
+        name: || t! {en: "Recording only", zh: "仅录制"}.to_string(),
+        description: || {
+            t! {
+                en: "Records to scrcpy-recording.mkv without playing anything.",
+                zh: "录制到 scrcpy-recording.mkv，不播放。"
+            }
+            .to_string()
+        },
+        overlay: r#"
+            video_playback = false
+            audio_playback = false
+            record = "scrcpy-recording.mkv"
+        "#,
+    },
+    Preset {
+        key: "webcam",
+        name: || t! {en: "Webcam", zh: "网络摄像头"}.to_string(),
+        description: || {
+            t! {
+                en: "Camera to the V4L2 device /dev/video0, without playback (Linux).",
+                zh: "摄像头输出到 V4L2 设备 /dev/video0，不播放（Linux）。"
+            }
+            .to_string()
+        },
+        overlay: r#"
+            video_source = "camera"
+            video_playback = false
+            audio_playback = false
+            v4l2 = "/dev/video0"
+        "#,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BitRate;
+    use crate::util::build_raw_args;
+
+    fn args(key: &str) -> Vec<String> {
+        let config = ConfigItemRaw::default().to_config();
+        let config = Preset::find(key).unwrap().apply(&config).unwrap();
+        shell_words::split(&build_raw_args(&config)).unwrap()
+    }
+
+    #[test]
+    fn presets_parse() {
+        for preset in PRESETS {
+            preset.overlay().unwrap();
+        }
+    }
+
+    #[test]
+    fn presets_only_touch_their_fields() {
+        let mut config = ConfigItemRaw::default().to_config();
+        config.serial = "R58M12345".into();
+        config.options.video_bit_rate = BitRate::new(8_000_000);
+        let config = Preset::find("gaming").unwrap().apply(&config).unwrap();
+        assert_eq!(config.serial, "R58M12345");
+        assert_eq!(config.options.video_bit_rate, BitRate::new(8_000_000));
+        assert_eq!(config.fps, Some(120));
+    }
+
+    #[test]
+    fn preset_args() {
+        let cases: &[(&str, &[&str])] = &[
+            ("low-latency", &["--video-bit-rate=4M", "--video-buffer=0"]),
+            ("quality", &["--video-codec=h265", "--video-bit-rate=16M"]),
+            (
+                "gaming",
+                &[
+                    "--keyboard=uhid",
+                    "--mouse=uhid",
+                    "--gamepad=uhid",
+                    "--max-fps=120",
+                ],
+            ),
+            (
+                "recording",
+                &["--no-playback", "--record=scrcpy-recording.mkv"],
+            ),
+            (
+                "webcam",
+                &[
+                    "--video-source=camera",
+                    "--no-playback",
+                    "--v4l2-sink=/dev/video0",
+                ],
+            ),
+        ];
+        for (key, expected) in cases {
+            let args = args(key);
+            for flag in *expected {
+                assert!(args.iter().any(|a| a == flag), "{key}: {flag} in {args:?}");
+            }
+        }
+        let config = ConfigItemRaw::default().to_config();
+        assert!(config.buffer_sync);
+        let config = Preset::find("low-latency").unwrap().apply(&config).unwrap();
+        assert!(!config.buffer_sync);
+    }
+}
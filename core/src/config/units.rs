@@ -0,0 +1,237 @@
+use crate::t;
+use crate::util::log;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Why the text of a typed field was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitError {
+    NotANumber,
+    BitRateSyntax,
+    OutOfRange { min: u32, max: u32 },
+}
+
+impl Display for UnitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::NotANumber => f.write_str(&t! {en: "expected a number", zh: "应为数字"}),
+            UnitError::BitRateSyntax => f.write_str(&t! {
+                en: "expected bits per second with an optional K or M suffix, e.g. 8M",
+                zh: "应为每秒比特数，可带 K 或 M 后缀，例如 8M"
+            }),
+            UnitError::OutOfRange { min, max } => f.write_str(&t! {
+                en: "expected {} to {}",
+                zh: "应在 {} 到 {} 之间",
+                (min, max)
+            }),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+/// A whole number in `min..=max`.
+fn parse_integer(s: &str, min: u32, max: u32) -> Result<u32, UnitError> {
+    let s = s.trim();
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(UnitError::NotANumber);
+    }
+    s.parse::<u64>()
+        .ok()
+        .filter(|n| (u64::from(min)..=u64::from(max)).contains(n))
+        .map(|n| n as u32)
+        .ok_or(UnitError::OutOfRange { min, max })
+}
+
+/// A value typed into a text field.
+pub trait FieldValue: Sized {
+    fn parse_field(s: &str) -> Result<Self, UnitError>;
+
+    /// `Ok(None)` for a blank field, which leaves the default.
+    fn parse_optional(s: &str) -> Result<Option<Self>, UnitError> {
+        match s.trim() {
+            "" => Ok(None),
+            s => Self::parse_field(s).map(Some),
+        }
+    }
+}
+
+impl FieldValue for u16 {
+    fn parse_field(s: &str) -> Result<Self, UnitError> {
+        parse_integer(s, 0, u16::MAX.into()).map(|n| n as u16)
+    }
+}
+
+impl FieldValue for u32 {
+    fn parse_field(s: &str) -> Result<Self, UnitError> {
+        parse_integer(s, 0, u32::MAX)
+    }
+}
+
+impl FieldValue for f32 {
+    fn parse_field(s: &str) -> Result<Self, UnitError> {
+        s.trim()
+            .parse()
+            .ok()
+            .filter(|n: &f32| n.is_finite())
+            .ok_or(UnitError::NotANumber)
+    }
+}
+
+/// Deserializes an optional field, dropping a value that isn't valid (such
+/// as the `0` older versions saved for blank sizes) instead of failing the
+/// whole file. Dropped values are logged.
+pub(crate) fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let Some(value) = Option::<toml::Value>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match T::deserialize(value.clone()) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            log(&format!("dropping invalid saved value {value}: {e}"));
+            Ok(None)
+        }
+    }
+}
+
+macro_rules! integer_units {
+    ($($(#[$meta:meta])* $name:ident: $min:expr, $max:expr;)*) => {$(
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(u32);
+
+        impl $name {
+            pub const MIN: u32 = $min;
+            pub const MAX: u32 = $max;
+
+            pub fn new(value: u32) -> Result<Self, UnitError> {
+                if (Self::MIN..=Self::MAX).contains(&value) {
+                    Ok(Self(value))
+                } else {
+                    Err(UnitError::OutOfRange {
+                        min: Self::MIN,
+                        max: Self::MAX,
+                    })
+                }
+            }
+
+            pub fn get(self) -> u32 {
+                self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnitError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_integer(s, Self::MIN, Self::MAX).map(Self)
+            }
+        }
+
+        impl FieldValue for $name {
+            fn parse_field(s: &str) -> Result<Self, UnitError> {
+                s.parse()
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(value: $name) -> u32 {
+                value.0
+            }
+        }
+
+        impl TryFrom<u32> for $name {
+            type Error = UnitError;
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Self::new(u32::deserialize(deserializer)?).map_err(de::Error::custom)
+            }
+        }
+    )*};
+}
+
+integer_units! {
+    /// Milliseconds, as scrcpy's buffers take them.
+    Millis: 0, i32::MAX as u32;
+    /// A width, height or size in pixels.
+    Dimension: 1, u16::MAX as u32;
+    /// Whole seconds, e.g. a time limit.
+    Seconds: 1, i32::MAX as u32;
+}
+
+impl Seconds {
+    /// How long a hook may run unless the profile says otherwise.
+    pub const DEFAULT_HOOK_TIMEOUT: Self = Self(30);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BitRate, ConfigItemRaw};
+
+    #[test]
+    fn integers_are_range_checked() {
+        assert_eq!(" 1920 ".parse(), Ok(Dimension(1920)));
+        assert_eq!("0".parse(), Ok(Millis(0)));
+        let dimension = UnitError::OutOfRange { min: 1, max: 65535 };
+        assert_eq!("0".parse::<Dimension>(), Err(dimension));
+        assert_eq!("70000".parse::<Dimension>(), Err(dimension));
+        assert_eq!("99999999999999999999".parse::<Dimension>(), Err(dimension));
+        for invalid in ["", "-1", "1.5", "12px", "0x10"] {
+            assert_eq!(
+                invalid.parse::<Seconds>(),
+                Err(UnitError::NotANumber),
+                "{invalid}"
+            );
+        }
+        assert_eq!(
+            u16::parse_field("65536"),
+            Err(UnitError::OutOfRange { min: 0, max: 65535 })
+        );
+        assert_eq!(f32::parse_field(" -90.5 "), Ok(-90.5));
+        assert_eq!(f32::parse_field("NaN"), Err(UnitError::NotANumber));
+        assert_eq!(Seconds::parse_optional(""), Ok(None));
+    }
+
+    #[test]
+    fn invalid_saved_values_are_dropped() {
+        let raw: ConfigItemRaw = toml::from_str(
+            r#"
+            display_width = 0
+            display_height = 1080
+            video_bit_rate = "8 M"
+            audio_bit_rate = "64K"
+            time_limit = -1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(raw.display_width, None);
+        assert_eq!(raw.display_height, Some(Dimension(1080)));
+        assert_eq!(raw.options.video_bit_rate, None);
+        assert_eq!(raw.options.audio_bit_rate, BitRate::new(64_000));
+        assert_eq!(raw.options.time_limit, None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// No config file exists at the given path yet.
+    ConfigNotFound(PathBuf),
+    /// A config or history file does not match its schema.
+    Parse(toml::de::Error),
+    /// A config or history could not be written as TOML.
+    Serialize(toml::ser::Error),
+    /// A launcher can't be written for the profile.
+    Launcher(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::ConfigNotFound(path) => write!(f, "config file {} not found", path.display()),
+            Error::Parse(e) => write!(f, "invalid config: {e}"),
+            Error::Serialize(e) => write!(f, "unable to write config: {e}"),
+            Error::Launcher(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::ConfigNotFound(_) | Error::Launcher(_) => None,
+            Error::Parse(e) => Some(e),
+            Error::Serialize(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(e: toml::ser::Error) -> Self {
+        Error::Serialize(e)
+    }
+}
@@ -1,3 +1,3 @@
-pub mod translate;
 mod language;
-pub use language::*;
\ No newline at end of file
+pub mod translate;
+pub use language::*;
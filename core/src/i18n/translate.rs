@@ -21,7 +21,7 @@ macro_rules! t {
         en: $fmt_en:expr,
         ($($args:tt)*)
     } => {
-        t! {
+        $crate::t! {
             en: $fmt_en,
             zh: $fmt_zh,
             ($($args)*)
@@ -31,7 +31,7 @@ macro_rules! t {
         zh: $fmt_zh:expr,
         en: $fmt_en:expr
     } => {
-        t! {
+        $crate::t! {
             en: $fmt_en,
             zh: $fmt_zh,
             ()
@@ -41,19 +41,19 @@ macro_rules! t {
         en: $fmt_en:expr,
         zh: $fmt_zh:expr
     } => {
-        t! {
+        $crate::t! {
             en: $fmt_en,
             zh: $fmt_zh,
             ()
         }
     };
-    
-    
+
+
     {r
         en: $fmt_en:expr,
         zh: $fmt_zh:expr
     } => {
-        
+
         *match &*$crate::i18n::LANGUAGE.read().unwrap(){
             $crate::i18n::Language::En => {
                 $fmt_en
@@ -67,9 +67,9 @@ macro_rules! t {
         zh: $fmt_zh:expr,
         en: $fmt_en:expr
     } => {
-        t! {r
+        $crate::t! {r
             en: $fmt_en,
             zh: $fmt_zh
         }
     };
-}
\ No newline at end of file
+}
@@ -0,0 +1,27 @@
+//! Profiles and command line generation for scrcpy, shared by the
+//! scrcpy-wrapper GUI and other tools.
+//!
+//! Profiles live in `scrcpy-wrapper.toml` under `$XDG_CONFIG_HOME` (or
+//! `~/.config`). [`config::ConfigRaw`] is the file as written, converted
+//! with [`config::ConfigRaw::to_config`] into a [`config::Config`]: the
+//! last used profile in `default`, the named ones in `saved`.
+//! [`util::build_args`] turns a profile into scrcpy arguments, optionally
+//! dropping the ones an older scrcpy (see [`util::ScrcpyInfo`]) lacks.
+//!
+//! ```no_run
+//! use scrcpy_wrapper_core::config::ConfigRaw;
+//! use scrcpy_wrapper_core::util::build_args;
+//!
+//! let config = ConfigRaw::load()?.to_config();
+//! for (name, profile) in &config.saved {
+//!     println!("{name}: scrcpy {}", build_args(profile, None));
+//! }
+//! # Ok::<(), scrcpy_wrapper_core::Error>(())
+//! ```
+
+pub mod config;
+mod error;
+pub mod i18n;
+pub mod util;
+
+pub use error::*;
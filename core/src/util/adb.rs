@@ -0,0 +1,352 @@
+use crate::config::Config;
+use crate::t;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// How to run adb for a config, detached from it so it can be moved into
+/// background tasks.
+#[derive(Debug, Clone)]
+pub struct Adb {
+    program: PathBuf,
+    env: Vec<(String, String)>,
+}
+
+impl Adb {
+    /// The configured adb, else the one shipped next to the scrcpy
+    /// executable, else `adb` from `PATH`, with the profile's environment.
+    pub fn new(config: &Config) -> Self {
+        #[cfg(target_os = "windows")]
+        let adb = "adb.exe";
+        #[cfg(not(target_os = "windows"))]
+        let adb = "adb";
+
+        let configured = config.adb().map(PathBuf::from);
+        let bundled = config
+            .default
+            .executable
+            .as_ref()
+            .and_then(|exe| Path::new(exe).parent().map(|dir| dir.join(adb)))
+            .filter(|path| path.is_file());
+
+        Self {
+            program: configured.or(bundled).unwrap_or(PathBuf::from(adb)),
+            env: config
+                .default
+                .env_vars()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .envs(self.env.iter().cloned())
+            .stdin(Stdio::null())
+            .stderr(Stdio::null());
+        command
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    pub serial: String,
+    pub state: String,
+    /// As adb lists it, with spaces and dashes turned into underscores.
+    pub model: Option<String>,
+}
+
+impl Display for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.model {
+            Some(model) => write!(f, "{} ({})", model.replace('_', " "), self.serial)?,
+            None => write!(f, "{}", self.serial)?,
+        }
+        if self.state != "device" {
+            write!(f, " [{}]", self.state)?;
+        }
+        Ok(())
+    }
+}
+
+impl Device {
+    pub fn list(adb: &Adb) -> Vec<Self> {
+        adb.command()
+            .args(["devices", "-l"])
+            .output()
+            .map(|output| Self::parse_list(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_default()
+    }
+
+    /// Parses `adb devices -l`, e.g.
+    /// `R58M12345 device usb:1-1 product:beyond2 model:SM_G975F transport_id:1`.
+    pub fn parse_list(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter(|l| !l.starts_with("List of devices") && !l.starts_with('*'))
+            .filter_map(|l| {
+                let mut fields = l.split_whitespace();
+                let serial = fields.next()?.to_string();
+                let state = fields.next()?.to_string();
+                let model = fields
+                    .find_map(|f| f.strip_prefix("model:"))
+                    .map(str::to_string);
+                Some(Self {
+                    serial,
+                    state,
+                    model,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BatteryStatus {
+    #[default]
+    Unknown,
+    Charging,
+    Discharging,
+    NotCharging,
+    Full,
+}
+
+impl Display for BatteryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                BatteryStatus::Unknown => t! {zh: "未知", en: "unknown"}.to_string(),
+                BatteryStatus::Charging => t! {zh: "充电中", en: "charging"}.to_string(),
+                BatteryStatus::Discharging => t! {zh: "放电中", en: "discharging"}.to_string(),
+                BatteryStatus::NotCharging => t! {zh: "未充电", en: "not charging"}.to_string(),
+                BatteryStatus::Full => t! {zh: "已充满", en: "full"}.to_string(),
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Battery {
+    pub level: u32,
+    pub status: BatteryStatus,
+    pub powered_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeviceInfo {
+    pub serial: String,
+    pub sdk: u32,
+    pub release: String,
+    pub model: String,
+    pub manufacturer: String,
+    pub abi: String,
+    pub screen_size: Option<(u32, u32)>,
+    pub density: Option<u32>,
+    pub battery: Option<Battery>,
+}
+
+const PROBE_SCRIPT: &str = "getprop ro.build.version.sdk; \
+    getprop ro.build.version.release; \
+    getprop ro.product.model; \
+    getprop ro.product.manufacturer; \
+    getprop ro.product.cpu.abi; \
+    wm size; \
+    wm density; \
+    dumpsys battery";
+
+impl DeviceInfo {
+    pub fn probe(adb: &Adb, serial: &str) -> Option<Self> {
+        let output = adb
+            .command()
+            .args(["-s", serial, "shell", PROBE_SCRIPT])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let mut info = Self::parse(&String::from_utf8_lossy(&output.stdout))?;
+        info.serial = serial.to_string();
+        Some(info)
+    }
+
+    /// Parses the output of [`PROBE_SCRIPT`]: one property per line, then the
+    /// output of `wm size`, `wm density` and `dumpsys battery`.
+    pub fn parse(output: &str) -> Option<Self> {
+        let mut lines = output.lines().map(|l| l.trim());
+        let sdk = lines.next()?.parse().ok()?;
+        let release = lines.next()?.to_string();
+        let model = lines.next()?.to_string();
+        let manufacturer = lines.next()?.to_string();
+        let abi = lines.next()?.to_string();
+        Some(Self {
+            serial: String::new(),
+            sdk,
+            release,
+            model,
+            manufacturer,
+            abi,
+            screen_size: parse_wm_size(output),
+            density: parse_wm_density(output),
+            battery: parse_battery(output),
+        })
+    }
+
+    /// Whether the device is reached over adb TCP/IP rather than USB.
+    pub fn is_tcp(&self) -> bool {
+        self.serial.contains(':') || self.serial.contains("._tcp")
+    }
+}
+
+fn find_value<'a>(output: &'a str, key: &str) -> Option<&'a str> {
+    output
+        .lines()
+        .find_map(|l| l.trim().strip_prefix(key))
+        .map(|v| v.trim())
+}
+
+/// Parses `1080x2400`.
+pub fn parse_size(s: &str) -> Option<(u32, u32)> {
+    let (width, height) = s.trim().split_once('x')?;
+    Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+}
+
+/// Parses `wm size`, preferring an override over the physical size.
+pub fn parse_wm_size(output: &str) -> Option<(u32, u32)> {
+    find_value(output, "Override size:")
+        .or_else(|| find_value(output, "Physical size:"))
+        .and_then(parse_size)
+}
+
+/// Parses `wm density`, preferring an override over the physical density.
+pub fn parse_wm_density(output: &str) -> Option<u32> {
+    find_value(output, "Override density:")
+        .or_else(|| find_value(output, "Physical density:"))
+        .and_then(|d| d.parse().ok())
+}
+
+/// Parses `dumpsys battery`.
+pub fn parse_battery(output: &str) -> Option<Battery> {
+    let level: u32 = find_value(output, "level:")?.parse().ok()?;
+    let scale: u32 = find_value(output, "scale:")
+        .and_then(|s| s.parse().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(100);
+    let status = match find_value(output, "status:") {
+        Some("2") => BatteryStatus::Charging,
+        Some("3") => BatteryStatus::Discharging,
+        Some("4") => BatteryStatus::NotCharging,
+        Some("5") => BatteryStatus::Full,
+        _ => BatteryStatus::Unknown,
+    };
+    let powered_by = ["AC", "USB", "Wireless", "Dock"]
+        .into_iter()
+        .filter(|source| find_value(output, &format!("{source} powered:")) == Some("true"))
+        .map(String::from)
+        .collect();
+    Some(Battery {
+        level: level * 100 / scale,
+        status,
+        powered_by,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROBE_OUTPUT: &str = "31
+12
+SM-G975F
+samsung
+arm64-v8a
+Physical size: 1440x3040
+Override size: 1080x2280
+Physical density: 560
+Override density: 420
+Current Battery Service state:
+  AC powered: false
+  USB powered: true
+  Wireless powered: false
+  Dock powered: false
+  Max charging current: 500000
+  Max charging voltage: 5000000
+  Charge counter: 2771000
+  status: 2
+  health: 2
+  present: true
+  level: 85
+  scale: 100
+  voltage: 4174
+  temperature: 290
+  technology: Li-ion
+";
+
+    #[test]
+    fn parse_probe_output() {
+        let info = DeviceInfo::parse(PROBE_OUTPUT).unwrap();
+        assert_eq!(info.sdk, 31);
+        assert_eq!(info.release, "12");
+        assert_eq!(info.model, "SM-G975F");
+        assert_eq!(info.manufacturer, "samsung");
+        assert_eq!(info.abi, "arm64-v8a");
+        assert_eq!(info.screen_size, Some((1080, 2280)));
+        assert_eq!(info.density, Some(420));
+        assert_eq!(
+            info.battery,
+            Some(Battery {
+                level: 85,
+                status: BatteryStatus::Charging,
+                powered_by: vec!["USB".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_wm_without_override() {
+        assert_eq!(
+            parse_wm_size("Physical size: 1080x2400\n"),
+            Some((1080, 2400))
+        );
+        assert_eq!(parse_wm_density("Physical density: 440\n"), Some(440));
+        assert_eq!(parse_wm_size("error: no devices/emulators found"), None);
+    }
+
+    #[test]
+    fn parse_battery_scale_and_status() {
+        let battery = parse_battery(
+            "Current Battery Service state:
+  AC powered: false
+  USB powered: false
+  status: 3
+  level: 50
+  scale: 200
+",
+        )
+        .unwrap();
+        assert_eq!(battery.level, 25);
+        assert_eq!(battery.status, BatteryStatus::Discharging);
+        assert!(battery.powered_by.is_empty());
+        assert_eq!(parse_battery("Can't find service: battery"), None);
+    }
+
+    #[test]
+    fn parse_device_list() {
+        let devices = Device::parse_list(
+            "List of devices attached
+R58M12345       device usb:1-1 product:beyond2 model:SM_G975F device:beyond2 transport_id:1
+192.168.1.20:5555 unauthorized transport_id:2
+
+",
+        );
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].serial, "R58M12345");
+        assert_eq!(devices[0].model.as_deref(), Some("SM_G975F"));
+        assert_eq!(devices[0].to_string(), "SM G975F (R58M12345)");
+        assert_eq!(devices[1].state, "unauthorized");
+        assert_eq!(devices[1].model, None);
+        assert_eq!(devices[1].to_string(), "192.168.1.20:5555 [unauthorized]");
+    }
+}
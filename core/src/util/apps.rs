@@ -0,0 +1,139 @@
+use crate::util::Adb;
+use std::fmt::Display;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct App {
+    pub label: Option<String>,
+    pub package: String,
+}
+
+impl Display for App {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{} ({})", label, self.package),
+            None => write!(f, "{}", self.package),
+        }
+    }
+}
+
+impl App {
+    /// Lists apps through `scrcpy --list-apps`, falling back to the third
+    /// party packages reported by `pm` when scrcpy is too old or fails.
+    pub fn list(adb: &Adb, executable: Option<&str>, serial: &str) -> Vec<Self> {
+        match executable.and_then(|exe| Self::list_scrcpy(exe, serial)) {
+            Some(apps) if !apps.is_empty() => apps,
+            _ => Self::list_pm(adb, serial),
+        }
+    }
+
+    fn list_scrcpy(exe: &str, serial: &str) -> Option<Vec<Self>> {
+        let mut command = Command::new(exe);
+        command.arg("--list-apps").stdin(Stdio::null());
+        if !serial.is_empty() {
+            command.arg(format!("--serial={serial}"));
+        }
+        let output = command.output().ok()?;
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Some(Self::parse_scrcpy_list(&text))
+    }
+
+    fn list_pm(adb: &Adb, serial: &str) -> Vec<Self> {
+        let mut command = adb.command();
+        if !serial.is_empty() {
+            command.args(["-s", serial]);
+        }
+        command
+            .args(["shell", "pm", "list", "packages", "-3"])
+            .output()
+            .map(|output| Self::parse_pm_list(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_default()
+    }
+
+    /// Parses `scrcpy --list-apps`, whose entries look like
+    /// ` * Calculator          com.google.android.calculator`.
+    pub fn parse_scrcpy_list(output: &str) -> Vec<Self> {
+        let mut apps: Vec<Self> = output
+            .lines()
+            .filter_map(|l| {
+                let l = l.trim();
+                let l = l.strip_prefix("* ").or_else(|| l.strip_prefix("- "))?;
+                let (label, package) = l.trim().rsplit_once(char::is_whitespace)?;
+                Some(Self {
+                    label: Some(label.trim().to_string()),
+                    package: package.to_string(),
+                })
+            })
+            .collect();
+        apps.sort_by_key(|a| a.label.clone().unwrap_or_default().to_lowercase());
+        apps
+    }
+
+    /// Parses `pm list packages`, one `package:com.example` per line.
+    pub fn parse_pm_list(output: &str) -> Vec<Self> {
+        let mut apps: Vec<Self> = output
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("package:"))
+            .map(|package| Self {
+                label: None,
+                package: package.to_string(),
+            })
+            .collect();
+        apps.sort_by(|a, b| a.package.cmp(&b.package));
+        apps
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.package.to_lowercase().contains(&query)
+            || self
+                .label
+                .as_ref()
+                .is_some_and(|l| l.to_lowercase().contains(&query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(label: Option<&str>, package: &str) -> App {
+        App {
+            label: label.map(str::to_string),
+            package: package.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_scrcpy_list_apps() {
+        let output = "\
+[server] INFO: Device: [Google] google Pixel 7 (Android 14)
+[server] INFO: List of apps:
+ * Calculator                     com.google.android.calculator
+ - Google Play Store              com.android.vending
+ - adb shell                      com.example.adb.shell
+ * ÇA Mobile                      fr.ca.mobile
+";
+        assert_eq!(
+            App::parse_scrcpy_list(output),
+            [
+                app(Some("adb shell"), "com.example.adb.shell"),
+                app(Some("Calculator"), "com.google.android.calculator"),
+                app(Some("Google Play Store"), "com.android.vending"),
+                app(Some("ÇA Mobile"), "fr.ca.mobile"),
+            ]
+        );
+        assert!(App::parse_scrcpy_list("ERROR: Could not find any ADB device\n").is_empty());
+    }
+
+    #[test]
+    fn parses_pm_list_packages() {
+        let output = "package:org.videolan.vlc\r\npackage:com.example.app\n\nWarning: junk\n";
+        assert_eq!(
+            App::parse_pm_list(output),
+            [app(None, "com.example.app"), app(None, "org.videolan.vlc"),]
+        );
+        assert!(App::parse_pm_list("error: no devices/emulators found\n").is_empty());
+    }
+}
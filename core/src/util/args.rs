@@ -0,0 +1,617 @@
+use crate::config::{
+    to_config_list, AppNameType, AudioCodec, AudioSource, BufferFlag, ConfigEnum, ConfigItem,
+    ConnectMethod, KeyInject, Keyboard, Mouse, MouseBind, OptionKey, OrientationAngle,
+    OrientationType, RenderDriver, VideoCodec, VideoSource,
+};
+use crate::util::{parse_port_range, strip_unsupported, ScrcpyInfo};
+use std::borrow::Cow;
+
+/// Arguments for `config`, without the options the scrcpy described by
+/// `info` does not know about. With no `info` every option is kept.
+pub fn build_args(config: &ConfigItem, info: Option<&ScrcpyInfo>) -> String {
+    strip_unsupported(info, &build_raw_args(config)).0
+}
+
+/// Arguments for `config` before options unknown to the detected scrcpy
+/// version are stripped.
+pub fn build_raw_args(config: &ConfigItem) -> String {
+    let mut args = String::new();
+    let have_audio = config.audio_source != AudioSource::No;
+    let have_video = config.video_source != VideoSource::No;
+
+    if !config.serial.trim().is_empty() {
+        args.push_str(" --serial=");
+        args.push_str(&quote(config.serial.trim()));
+    }
+
+    if let ConnectMethod::Otg = config.connect_method {
+        args.push_str(" --otg");
+    } else if parse_port_range(&config.port).is_some() {
+        args.push_str(" --port=");
+        args.push_str(config.port.trim());
+    }
+    push_options(
+        &mut args,
+        config,
+        &[
+            OptionKey::TunnelHost,
+            OptionKey::TunnelPort,
+            OptionKey::ForceAdbForward,
+            OptionKey::KillAdbOnClose,
+            OptionKey::Cleanup,
+            OptionKey::DownsizeOnError,
+        ],
+    );
+
+    match config.video_source {
+        VideoSource::No => {
+            args.push_str(" --no-video");
+        }
+        VideoSource::Display => {}
+        VideoSource::Camera => {
+            args.push_str(" --video-source=camera");
+            push_options(&mut args, config, &[OptionKey::Camera]);
+        }
+    }
+
+    if have_video {
+        push_options(&mut args, config, &[OptionKey::VideoSize]);
+
+        match &config.video_codec {
+            VideoCodec::H264 => {}
+            VideoCodec::H265 => {
+                args.push_str(" --video-codec=h265");
+            }
+            VideoCodec::Av1 => {
+                args.push_str(" --video-codec=av1");
+            }
+        }
+
+        if !config.video_codec_options.trim().is_empty() {
+            let opts: Vec<&str> = config.video_codec_options.split_whitespace().collect();
+            args.push_str(" --video-codec-options=");
+            args.push_str(&quote(&opts.join(",")));
+        }
+
+        if (config.orientation_angle != OrientationAngle::Default)
+            || (config.orientation_type == OrientationType::Capture && config.orientation_lock)
+        {
+            args.push_str(match config.orientation_type {
+                OrientationType::Client => " --orientation=",
+                OrientationType::Capture => " --capture-orientation=",
+                OrientationType::Display => " --display-orientation=",
+            });
+            if config.orientation_type == OrientationType::Capture && config.orientation_lock {
+                args.push('@');
+            }
+            if config.orientation_angle != OrientationAngle::Default && config.orientation_flip {
+                args.push_str("flip");
+            }
+            if config.orientation_angle != OrientationAngle::Default {
+                args.push_str(match config.orientation_angle {
+                    OrientationAngle::Default => "0",
+                    OrientationAngle::_0 => "0",
+                    OrientationAngle::_90 => "90",
+                    OrientationAngle::_180 => "180",
+                    OrientationAngle::_270 => "270",
+                });
+            }
+        }
+
+        push_options(&mut args, config, &[OptionKey::Angle]);
+
+        if config.video_playback
+            && config.render_driver != RenderDriver::Default
+            && RenderDriver::available().contains(&config.render_driver)
+        {
+            args.push_str(" --render-driver=");
+            args.push_str(&config.render_driver.to_config_string());
+        }
+        push_options(&mut args, config, &[OptionKey::Mipmaps]);
+    }
+
+    match config.audio_source {
+        AudioSource::No => {
+            args.push_str(" --no-audio");
+        }
+        AudioSource::Output => {}
+        AudioSource::Playback => {
+            args.push_str(" --audio-source=playback");
+            push_options(&mut args, config, &[OptionKey::AudioDup]);
+        }
+        _ => {
+            if config.video_source != VideoSource::Camera {
+                args.push_str(&format!(
+                    " --audio-source={}",
+                    config.audio_source.to_config_string()
+                ));
+            }
+        }
+    }
+
+    if have_audio {
+        match &config.audio_codec {
+            AudioCodec::Opus => {}
+            AudioCodec::Aac => {
+                args.push_str(" --audio-codec=aac");
+            }
+            AudioCodec::Flac => {
+                args.push_str(" --audio-codec=flac");
+            }
+            AudioCodec::Raw => {
+                args.push_str(" --audio-codec=raw");
+            }
+        }
+
+        if !config.audio_codec_options.trim().is_empty() {
+            let opts: Vec<&str> = config.audio_codec_options.split_whitespace().collect();
+            args.push_str(" --audio-codec-options=");
+            args.push_str(&quote(&opts.join(",")));
+        }
+    }
+
+    if have_audio && have_video && !config.audio_playback && !config.video_playback {
+        args.push_str(" --no-playback");
+    } else {
+        if have_audio && !config.audio_playback {
+            args.push_str(" --no-audio-playback");
+        }
+        if have_video && !config.video_playback {
+            args.push_str(" --no-video-playback");
+        }
+    }
+
+    push_options(
+        &mut args,
+        config,
+        &[OptionKey::VideoBitRate, OptionKey::AudioBitRate],
+    );
+
+    if let Some(fps) = config.fps {
+        match config.video_source {
+            VideoSource::No => {}
+            VideoSource::Display => {
+                args.push_str(" --max-fps=");
+                args.push_str(&fps.to_string());
+            }
+            VideoSource::Camera => {
+                args.push_str(" --camera-fps=");
+                args.push_str(&fps.to_string());
+            }
+        }
+    }
+
+    if have_video {
+        if let Some(buffer) = config.video_buffer {
+            args.push_str(match config.buffer_flag {
+                BufferFlag::Video => " --video-buffer=",
+                BufferFlag::Display => " --display-buffer=",
+            });
+            args.push_str(&buffer.to_string());
+        }
+    }
+    push_options(
+        &mut args,
+        config,
+        &[OptionKey::AudioBuffer, OptionKey::PrintFps],
+    );
+
+    match config.keyboard {
+        Keyboard::Sdk => {}
+        Keyboard::Uhid => {
+            args.push_str(" --keyboard=uhid");
+        }
+        Keyboard::Aoa => {
+            args.push_str(" --keyboard=aoa");
+        }
+        Keyboard::Disabled => {
+            args.push_str(" --keyboard=disabled");
+        }
+    }
+    match config.mouse {
+        Mouse::Sdk => {}
+        Mouse::Uhid => {
+            args.push_str(" --mouse=uhid");
+        }
+        Mouse::Aoa => {
+            args.push_str(" --mouse=aoa");
+        }
+        Mouse::Disabled => {
+            args.push_str(" --mouse=disabled");
+        }
+    }
+    push_options(&mut args, config, &[OptionKey::Gamepad]);
+
+    if config.options.control {
+        if config.keyboard == Keyboard::Sdk {
+            match config.key_inject {
+                KeyInject::Mixed => {}
+                KeyInject::PreferText => {
+                    args.push_str(" --prefer-text");
+                }
+                KeyInject::RawKeyEvents => {
+                    args.push_str(" --raw-key-events");
+                }
+            }
+        }
+        push_options(
+            &mut args,
+            config,
+            &[
+                OptionKey::KeyRepeat,
+                OptionKey::LegacyPaste,
+                OptionKey::ClipboardAutosync,
+                OptionKey::MouseHover,
+            ],
+        );
+        if config.mouse != Mouse::Disabled {
+            if let Some(bindings) = &config.mouse_bind {
+                args.push_str(" --mouse-bind=");
+                args.push_str(&MouseBind::to_config_bindings(bindings));
+            }
+        }
+    }
+    push_options(&mut args, config, &[OptionKey::Control]);
+
+    if !config.shortcut_mod.is_empty() {
+        args.push_str(" --shortcut-mod=");
+        args.push_str(&to_config_list(&config.shortcut_mod));
+    }
+
+    push_options(
+        &mut args,
+        config,
+        &[OptionKey::Record, OptionKey::V4l2, OptionKey::V4l2Buffer],
+    );
+
+    if config.virtual_display {
+        args.push_str(" --new-display");
+        // without a size, scrcpy uses the size of the main display
+        if let (Some(width), Some(height)) = (config.display_width, config.display_height) {
+            args.push_str(&format!("={width}x{height}"));
+        }
+        push_options(
+            &mut args,
+            config,
+            &[OptionKey::DisplayImePolicy, OptionKey::DestroyAppOnClose],
+        );
+    }
+
+    if !config.start_app.trim().is_empty() {
+        args.push_str(" --start-app=");
+        if config.restart_app {
+            args.push('+');
+        }
+        if config.app_name_type == AppNameType::AppName {
+            args.push('?');
+        }
+        args.push_str(&quote(config.start_app.trim()));
+    }
+
+    push_options(
+        &mut args,
+        config,
+        &[
+            OptionKey::TimeLimit,
+            OptionKey::StayAwake,
+            OptionKey::DisableWindow,
+            OptionKey::Borderless,
+            OptionKey::AlwaysOnTop,
+            OptionKey::Fullscreen,
+            OptionKey::DisableScreensaver,
+        ],
+    );
+
+    if !config.additional_args.trim().is_empty() {
+        args.push(' ');
+        args.push_str(config.additional_args.trim());
+    }
+
+    args.trim().to_string()
+}
+
+/// `value` as a single shell word, quoted only if needed; unlike
+/// `shell_words::quote` this leaves `key=value` lists alone.
+pub(crate) fn quote(value: &str) -> Cow<'_, str> {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.,:=+/@%".contains(c))
+    {
+        Cow::Borrowed(value)
+    } else {
+        shell_words::quote(value)
+    }
+}
+
+/// Appends the registered options in `keys` that apply to `config`.
+fn push_options(args: &mut String, config: &ConfigItem, keys: &[OptionKey]) {
+    for key in keys {
+        if !(key.spec().applies)(config) {
+            continue;
+        }
+        if let Some(arg) = config.options.arg(*key) {
+            args.push(' ');
+            args.push_str(&arg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        arbitrary, BitRate, ButtonState, Camera, ConfigItemRaw, Dimension, DisplayImePolicy,
+        Gamepad, Millis, OptionValue, ShortcutMod,
+    };
+
+    type Case = (&'static str, fn(&mut ConfigItem), &'static str);
+
+    fn base() -> ConfigItem {
+        let mut config = ConfigItemRaw::default().to_config();
+        config.executable = Some("scrcpy".to_string());
+        config
+    }
+
+    fn set(config: &mut ConfigItem, key: OptionKey, value: OptionValue) {
+        config.options.set(key, value);
+    }
+
+    const CASES: &[Case] = &[
+        ("defaults", |_| {}, ""),
+        ("serial", |c| c.serial = " R58M12345 ".into(), "--serial=R58M12345"),
+        ("wireless serial", |c| c.serial = "192.168.1.2:5555".into(), "--serial=192.168.1.2:5555"),
+        ("port", |c| c.port = "27183:27199".into(), "--port=27183:27199"),
+        ("invalid port", |c| c.port = "abc".into(), ""),
+        ("otg", |c| {
+            c.connect_method = ConnectMethod::Otg;
+            c.port = "27183".into();
+            set(c, OptionKey::ForceAdbForward, OptionValue::Switch(true));
+        }, "--otg"),
+        ("tunnel", |c| {
+            set(c, OptionKey::TunnelHost, OptionValue::Text("host".into()));
+            set(c, OptionKey::TunnelPort, OptionValue::Number(Some(1234)));
+            set(c, OptionKey::ForceAdbForward, OptionValue::Switch(true));
+            set(c, OptionKey::KillAdbOnClose, OptionValue::Switch(true));
+            set(c, OptionKey::Cleanup, OptionValue::Switch(false));
+            set(c, OptionKey::DownsizeOnError, OptionValue::Switch(false));
+        }, "--tunnel-host=host --tunnel-port=1234 --force-adb-forward --kill-adb-on-close --no-cleanup --no-downsize-on-error"),
+
+        ("no video", |c| c.video_source = VideoSource::No, "--no-video"),
+        ("no video drops video options", |c| {
+            c.video_source = VideoSource::No;
+            c.options.video_size = Dimension::new(1024).ok();
+            c.video_codec = VideoCodec::H265;
+            c.options.video_bit_rate = BitRate::new(8_000_000);
+            c.video_buffer = Millis::new(50).ok();
+            c.fps = Some(60);
+        }, "--no-video"),
+        ("camera", |c| c.video_source = VideoSource::Camera, "--video-source=camera"),
+        ("camera front", |c| {
+            c.video_source = VideoSource::Camera;
+            c.options.camera = Camera::Front;
+        }, "--video-source=camera --camera-facing=front"),
+        ("camera back", |c| {
+            c.video_source = VideoSource::Camera;
+            c.options.camera = Camera::Back;
+        }, "--video-source=camera --camera-facing=back"),
+        ("camera external", |c| {
+            c.video_source = VideoSource::Camera;
+            c.options.camera = Camera::External;
+        }, "--video-source=camera --camera-facing=external"),
+        ("camera on display", |c| c.options.camera = Camera::Front, ""),
+        ("video size", |c| c.options.video_size = Dimension::new(1024).ok(), "--max-size=1024"),
+        ("h265", |c| c.video_codec = VideoCodec::H265, "--video-codec=h265"),
+        ("av1", |c| c.video_codec = VideoCodec::Av1, "--video-codec=av1"),
+        ("video codec options", |c| c.video_codec_options = "profile=1  level=52".into(), "--video-codec-options=profile=1,level=52"),
+
+        ("orientation", |c| c.orientation_angle = OrientationAngle::_90, "--orientation=90"),
+        ("orientation 0", |c| c.orientation_angle = OrientationAngle::_0, "--orientation=0"),
+        ("orientation 180", |c| c.orientation_angle = OrientationAngle::_180, "--orientation=180"),
+        ("orientation flip", |c| {
+            c.orientation_angle = OrientationAngle::_270;
+            c.orientation_flip = true;
+        }, "--orientation=flip270"),
+        ("flip without angle", |c| c.orientation_flip = true, ""),
+        ("lock without capture", |c| c.orientation_lock = true, ""),
+        ("capture lock", |c| {
+            c.orientation_type = OrientationType::Capture;
+            c.orientation_lock = true;
+        }, "--capture-orientation=@"),
+        ("capture lock flip", |c| {
+            c.orientation_type = OrientationType::Capture;
+            c.orientation_lock = true;
+            c.orientation_angle = OrientationAngle::_180;
+            c.orientation_flip = true;
+        }, "--capture-orientation=@flip180"),
+        ("display orientation", |c| {
+            c.orientation_type = OrientationType::Display;
+            c.orientation_angle = OrientationAngle::_0;
+        }, "--display-orientation=0"),
+        ("angle", |c| c.options.angle = Some(12.5), "--angle=12.5"),
+        ("render driver", |c| c.render_driver = RenderDriver::OpenGl, "--render-driver=opengl"),
+        ("render driver without playback", |c| {
+            c.render_driver = RenderDriver::OpenGl;
+            c.video_playback = false;
+            set(c, OptionKey::Mipmaps, OptionValue::Switch(false));
+        }, "--no-video-playback"),
+        ("mipmaps", |c| set(c, OptionKey::Mipmaps, OptionValue::Switch(false)), "--no-mipmaps"),
+
+        ("no audio", |c| c.audio_source = AudioSource::No, "--no-audio"),
+        ("no audio drops audio options", |c| {
+            c.audio_source = AudioSource::No;
+            c.audio_codec = AudioCodec::Aac;
+            c.options.audio_bit_rate = BitRate::new(128_000);
+            c.options.audio_buffer = Millis::new(40).ok();
+        }, "--no-audio"),
+        ("playback", |c| c.audio_source = AudioSource::Playback, "--audio-source=playback"),
+        ("playback dup", |c| {
+            c.audio_source = AudioSource::Playback;
+            c.options.audio_dup = true;
+        }, "--audio-source=playback --audio-dup"),
+        ("dup without playback", |c| c.options.audio_dup = true, ""),
+        ("mic", |c| c.audio_source = AudioSource::Mic, "--audio-source=mic"),
+        ("mic with camera", |c| {
+            c.video_source = VideoSource::Camera;
+            c.audio_source = AudioSource::Mic;
+        }, "--video-source=camera"),
+        ("aac", |c| c.audio_codec = AudioCodec::Aac, "--audio-codec=aac"),
+        ("flac", |c| c.audio_codec = AudioCodec::Flac, "--audio-codec=flac"),
+        ("raw", |c| c.audio_codec = AudioCodec::Raw, "--audio-codec=raw"),
+        ("audio codec options", |c| c.audio_codec_options = "flac-compression-level=8".into(), "--audio-codec-options=flac-compression-level=8"),
+
+        ("no playback", |c| {
+            c.video_playback = false;
+            c.audio_playback = false;
+        }, "--no-playback"),
+        ("no audio playback", |c| c.audio_playback = false, "--no-audio-playback"),
+        ("no video playback", |c| c.video_playback = false, "--no-video-playback"),
+        ("no video playback without audio", |c| {
+            c.audio_source = AudioSource::No;
+            c.video_playback = false;
+        }, "--no-audio --no-video-playback"),
+        ("no audio playback without video", |c| {
+            c.video_source = VideoSource::No;
+            c.audio_playback = false;
+            c.video_playback = false;
+        }, "--no-video --no-audio-playback"),
+
+        ("bit rates", |c| {
+            c.options.video_bit_rate = BitRate::new(8_000_000);
+            c.options.audio_bit_rate = BitRate::new(128_000);
+        }, "--video-bit-rate=8M --audio-bit-rate=128K"),
+        ("raw audio bit rate", |c| {
+            c.audio_codec = AudioCodec::Raw;
+            c.options.audio_bit_rate = BitRate::new(128_000);
+        }, "--audio-codec=raw"),
+        ("max fps", |c| c.fps = Some(60), "--max-fps=60"),
+        ("print fps", |c| set(c, OptionKey::PrintFps, OptionValue::Switch(true)), "--print-fps"),
+        ("print fps without playback", |c| {
+            c.video_playback = false;
+            set(c, OptionKey::PrintFps, OptionValue::Switch(true));
+        }, "--no-video-playback"),
+        ("camera fps", |c| {
+            c.video_source = VideoSource::Camera;
+            c.fps = Some(30);
+        }, "--video-source=camera --camera-fps=30"),
+        ("video buffer", |c| c.video_buffer = Millis::new(50).ok(), "--video-buffer=50"),
+        ("display buffer", |c| {
+            c.video_buffer = Millis::new(50).ok();
+            c.buffer_flag = BufferFlag::Display;
+        }, "--display-buffer=50"),
+        ("audio buffer", |c| c.options.audio_buffer = Millis::new(40).ok(), "--audio-buffer=40"),
+
+        ("keyboard uhid", |c| c.keyboard = Keyboard::Uhid, "--keyboard=uhid"),
+        ("keyboard aoa", |c| c.keyboard = Keyboard::Aoa, "--keyboard=aoa"),
+        ("keyboard disabled", |c| c.keyboard = Keyboard::Disabled, "--keyboard=disabled"),
+        ("mouse uhid", |c| c.mouse = Mouse::Uhid, "--mouse=uhid"),
+        ("mouse aoa", |c| c.mouse = Mouse::Aoa, "--mouse=aoa"),
+        ("mouse disabled", |c| c.mouse = Mouse::Disabled, "--mouse=disabled"),
+        ("gamepad uhid", |c| c.options.gamepad = Gamepad::Uhid, "--gamepad=uhid"),
+        ("gamepad aoa", |c| c.options.gamepad = Gamepad::Aoa, "--gamepad=aoa"),
+        ("no control", |c| {
+            c.options.control = false;
+            c.key_inject = KeyInject::PreferText;
+            set(c, OptionKey::KeyRepeat, OptionValue::Switch(false));
+            set(c, OptionKey::ClipboardAutosync, OptionValue::Switch(false));
+        }, "--no-control"),
+        ("prefer text", |c| c.key_inject = KeyInject::PreferText, "--prefer-text"),
+        ("raw key events", |c| c.key_inject = KeyInject::RawKeyEvents, "--raw-key-events"),
+        ("key inject without sdk keyboard", |c| {
+            c.keyboard = Keyboard::Uhid;
+            c.key_inject = KeyInject::RawKeyEvents;
+            set(c, OptionKey::KeyRepeat, OptionValue::Switch(false));
+            set(c, OptionKey::LegacyPaste, OptionValue::Switch(true));
+        }, "--keyboard=uhid"),
+        ("keyboard options", |c| {
+            set(c, OptionKey::KeyRepeat, OptionValue::Switch(false));
+            set(c, OptionKey::LegacyPaste, OptionValue::Switch(true));
+            set(c, OptionKey::ClipboardAutosync, OptionValue::Switch(false));
+        }, "--no-key-repeat --legacy-paste --no-clipboard-autosync"),
+        ("mouse hover", |c| set(c, OptionKey::MouseHover, OptionValue::Switch(false)), "--no-mouse-hover"),
+        ("mouse hover without sdk mouse", |c| {
+            c.mouse = Mouse::Uhid;
+            set(c, OptionKey::MouseHover, OptionValue::Switch(false));
+        }, "--mouse=uhid"),
+        ("mouse bind", |c| c.mouse_bind = Some(MouseBind::defaults(Mouse::Sdk)), "--mouse-bind=bhsn:++++"),
+        ("mouse bind without mouse", |c| {
+            c.mouse = Mouse::Disabled;
+            c.mouse_bind = Some(MouseBind::defaults(Mouse::Sdk));
+        }, "--mouse=disabled"),
+        ("shortcut mod", |c| c.shortcut_mod = vec![ShortcutMod::LAlt, ShortcutMod::RCtrl], "--shortcut-mod=lalt,rctrl"),
+
+        ("record", |c| set(c, OptionKey::Record, OptionValue::Text("my file.mkv".into())), "--record='my file.mkv'"),
+        ("v4l2", |c| set(c, OptionKey::V4l2, OptionValue::Text("/dev/video2".into())), "--v4l2-sink=/dev/video2"),
+        ("v4l2 buffer", |c| {
+            set(c, OptionKey::V4l2, OptionValue::Text("/dev/video2".into()));
+            set(c, OptionKey::V4l2Buffer, OptionValue::Number(Some(300)));
+        }, "--v4l2-sink=/dev/video2 --v4l2-buffer=300"),
+        ("v4l2 buffer without sink", |c| set(c, OptionKey::V4l2Buffer, OptionValue::Number(Some(300))), ""),
+        ("virtual display", |c| {
+            c.virtual_display = true;
+            c.display_width = Dimension::new(1920).ok();
+            c.display_height = Dimension::new(1080).ok();
+        }, "--new-display=1920x1080"),
+        ("virtual display local ime", |c| {
+            c.virtual_display = true;
+            c.options.display_ime_policy = DisplayImePolicy::Local;
+        }, "--new-display --display-ime-policy=local"),
+        ("virtual display hidden ime", |c| {
+            c.virtual_display = true;
+            c.options.display_ime_policy = DisplayImePolicy::Hide;
+            c.options.destroy_app_on_close = false;
+        }, "--new-display --display-ime-policy=hide --no-vd-destroy-content"),
+        ("start app", |c| c.start_app = "org.mozilla.firefox".into(), "--start-app=org.mozilla.firefox"),
+        ("restart app by name", |c| {
+            c.start_app = "Firefox".into();
+            c.restart_app = true;
+            c.app_name_type = AppNameType::AppName;
+        }, "--start-app=+?Firefox"),
+        ("others", |c| {
+            set(c, OptionKey::TimeLimit, OptionValue::Number(Some(60)));
+            set(c, OptionKey::StayAwake, OptionValue::Switch(true));
+            set(c, OptionKey::DisableWindow, OptionValue::Switch(true));
+            set(c, OptionKey::Borderless, OptionValue::Switch(true));
+            set(c, OptionKey::AlwaysOnTop, OptionValue::Switch(true));
+            set(c, OptionKey::Fullscreen, OptionValue::Switch(true));
+            set(c, OptionKey::DisableScreensaver, OptionValue::Switch(true));
+        }, "--time-limit=60 --stay-awake --no-window --window-borderless --always-on-top --fullscreen --no-screensaver"),
+        ("additional args", |c| c.additional_args = " --verbosity=debug ".into(), "--verbosity=debug"),
+    ];
+
+    #[test]
+    fn golden() {
+        for (name, edit, expected) in CASES {
+            let mut config = base();
+            edit(&mut config);
+            assert_eq!(build_raw_args(&config), *expected, "case {name}");
+        }
+    }
+
+    #[test]
+    fn audio_sources() {
+        for source in AudioSource::states() {
+            let mut config = base();
+            config.audio_source = source;
+            let expected = match source {
+                AudioSource::No => "--no-audio".to_string(),
+                AudioSource::Output => String::new(),
+                _ => format!("--audio-source={}", source.to_config_string()),
+            };
+            assert_eq!(build_raw_args(&config), expected, "{source:?}");
+        }
+    }
+
+    #[test]
+    fn args_always_parse() {
+        for seed in 0..500 {
+            let config = arbitrary::config(&mut fastrand::Rng::with_seed(seed));
+            let args = build_raw_args(&config);
+            let tokens =
+                shell_words::split(&args).unwrap_or_else(|e| panic!("seed {seed}: {e} in {args}"));
+            let serial = config.serial.trim();
+            if !serial.is_empty() {
+                assert_eq!(tokens[0], format!("--serial={serial}"), "seed {seed}");
+            }
+        }
+    }
+}
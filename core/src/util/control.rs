@@ -0,0 +1,549 @@
+use crate::config::{Config, ConfigItem, ConfigRaw};
+use crate::util::{
+    build_args, child_env, log, Adb, Launcher, PerfMonitor, ScrcpyInfo, SessionEnd, Stopper,
+};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Where the control API listens: `control_socket` from the config, else
+/// `scrcpy-wrapper.sock` in `$XDG_RUNTIME_DIR` or the temp directory.
+pub fn control_socket(config: &Config) -> PathBuf {
+    if let Some(path) = config
+        .control_socket
+        .as_ref()
+        .filter(|p| !p.trim().is_empty())
+    {
+        return PathBuf::from(path);
+    }
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("scrcpy-wrapper.sock")
+}
+
+struct Session {
+    id: u64,
+    profile: Option<String>,
+    serial: String,
+    stopper: Stopper,
+    /// How the session ended, `None` while it runs.
+    end: Arc<Mutex<Option<SessionEnd>>>,
+    perf: Arc<Mutex<PerfMonitor>>,
+    thread: JoinHandle<()>,
+}
+
+impl Session {
+    fn ended(&self) -> bool {
+        self.end.lock().unwrap().is_some()
+    }
+
+    fn status(&self) -> SessionStatus {
+        let end = self.end.lock().unwrap().clone();
+        let (name, exit_code, error) = match end {
+            None => ("running", None, None),
+            Some(SessionEnd::Exited(code)) => ("exited", code, None),
+            Some(SessionEnd::Stopped) => ("stopped", None, None),
+            Some(SessionEnd::Failed(e)) => ("failed", None, Some(e)),
+        };
+        SessionStatus {
+            session: self.id,
+            profile: self.profile.clone(),
+            serial: self.serial.clone(),
+            state: name,
+            exit_code,
+            error,
+            fps: self.fps(),
+        }
+    }
+
+    /// Live FPS figures while the session runs with the monitor.
+    fn fps(&self) -> Option<FpsStatus> {
+        let perf = self.perf.lock().unwrap();
+        let summary = perf.summary()?;
+        Some(FpsStatus {
+            current: perf.samples().last().copied(),
+            min: summary.min_fps,
+            avg: summary.avg_fps,
+            max: summary.max_fps,
+            skipped_frames: summary.skipped_frames,
+            buffering_events: summary.buffering_events,
+        })
+    }
+}
+
+/// A request line, e.g. `{"cmd":"stop","session":1}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    ListProfiles,
+    GetArgs(Target),
+    Launch(Target),
+    Stop { session: u64 },
+    Status,
+    Reload,
+}
+
+/// The profile a request is about, see [`profile`].
+#[derive(Debug, Deserialize)]
+struct Target {
+    profile: Option<String>,
+    serial: Option<String>,
+}
+
+/// What a request returns, next to `"ok"` in the response line.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum Response {
+    Profiles {
+        profiles: Vec<String>,
+    },
+    Args {
+        executable: Option<String>,
+        args: String,
+    },
+    Launched {
+        session: u64,
+    },
+    Done {},
+    Status {
+        sessions: Vec<SessionStatus>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Reply {
+    ok: bool,
+    #[serde(flatten)]
+    response: Option<Response>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionStatus {
+    session: u64,
+    profile: Option<String>,
+    serial: String,
+    state: &'static str,
+    exit_code: Option<i32>,
+    error: Option<String>,
+    fps: Option<FpsStatus>,
+}
+
+#[derive(Debug, Serialize)]
+struct FpsStatus {
+    current: Option<u32>,
+    min: Option<u32>,
+    avg: Option<f32>,
+    max: Option<u32>,
+    skipped_frames: u64,
+    buffering_events: u32,
+}
+
+/// Drives the wrapper from other programs: one JSON request per line, e.g.
+/// `{"cmd":"launch","profile":"phone","serial":"R58M12345"}`, answered with
+/// one line holding `"ok"` and either the result or an `"error"`.
+///
+/// Commands are `list_profiles`, `launch {profile, serial}`,
+/// `stop {session}`, `status`, `get_args {profile, serial}` and `reload`.
+/// Profiles are the saved ones, read from the config file when the server
+/// is created and again on `reload`; without a `profile` the default
+/// config is used.
+///
+/// Launched sessions don't belong to the connection that launched them:
+/// they run until scrcpy exits or [`shutdown`](Self::shutdown) stops them.
+/// Ended sessions are reported by the next `status` and then forgotten, as
+/// they are on the next `launch`.
+pub struct ControlServer {
+    config: Mutex<Config>,
+    sessions: Mutex<Vec<Session>>,
+    next_id: AtomicU64,
+}
+
+impl ControlServer {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: Mutex::new(config),
+            sessions: Mutex::default(),
+            next_id: AtomicU64::default(),
+        }
+    }
+
+    /// A server for the config file, with the installs detected once.
+    pub fn load() -> Self {
+        Self::new(load_config())
+    }
+
+    /// Answers a request line.
+    pub fn handle(&self, line: &str) -> String {
+        let config = self.config.lock().unwrap().clone();
+        let result = serde_json::from_str(line)
+            .map_err(|e| e.to_string())
+            .and_then(|request| self.dispatch(request, &config));
+        let reply = match result {
+            Ok(response) => Reply {
+                ok: true,
+                response: Some(response),
+                error: None,
+            },
+            Err(e) => Reply {
+                ok: false,
+                response: None,
+                error: Some(e),
+            },
+        };
+        serde_json::to_string(&reply).expect("replies serialize")
+    }
+
+    fn dispatch(&self, request: Request, config: &Config) -> Result<Response, String> {
+        match request {
+            Request::ListProfiles => {
+                let mut profiles: Vec<_> = config.saved.keys().cloned().collect();
+                profiles.sort();
+                Ok(Response::Profiles { profiles })
+            }
+            Request::GetArgs(target) => {
+                let (_, item) = profile(target, config)?;
+                Ok(Response::Args {
+                    args: build_args(&item, scrcpy_info(&item).as_ref()),
+                    executable: item.executable,
+                })
+            }
+            Request::Launch(target) => {
+                let (profile, item) = profile(target, config)?;
+                let session = self.launch(profile, item, config)?;
+                Ok(Response::Launched { session })
+            }
+            Request::Stop { session: id } => {
+                let sessions = self.sessions.lock().unwrap();
+                let session = sessions
+                    .iter()
+                    .find(|s| s.id == id)
+                    .ok_or(format!("unknown session {id}"))?;
+                session.stopper.stop();
+                Ok(Response::Done {})
+            }
+            Request::Status => {
+                let mut sessions = self.sessions.lock().unwrap();
+                let status: Vec<_> = sessions.iter().map(Session::status).collect();
+                // only those reported as ended, one may have ended meanwhile
+                prune(&mut sessions, |s| {
+                    status
+                        .iter()
+                        .any(|r| r.session == s.id && r.state != "running")
+                });
+                Ok(Response::Status { sessions: status })
+            }
+            Request::Reload => {
+                *self.config.lock().unwrap() = load_config();
+                Ok(Response::Done {})
+            }
+        }
+    }
+
+    /// Starts a session the way the window does, see
+    /// [`Launcher::run_session`], on a thread of its own.
+    fn launch(
+        &self,
+        profile: Option<String>,
+        item: ConfigItem,
+        config: &Config,
+    ) -> Result<u64, String> {
+        prune(&mut self.sessions.lock().unwrap(), Session::ended);
+        let executable = item
+            .executable
+            .clone()
+            .ok_or("no scrcpy executable configured")?;
+        let env = child_env(config.adb(), &item);
+        let args = build_args(&item, scrcpy_info(&item).as_ref());
+        let serial = item.serial.clone();
+        let mut launcher = Launcher::new(executable, args, item, profile.clone(), env);
+        let adb = Adb::new(config);
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let stopper = launcher.stopper();
+        let perf = launcher.perf();
+        let end = Arc::new(Mutex::new(None));
+
+        let thread = thread::spawn({
+            let end = end.clone();
+            move || *end.lock().unwrap() = Some(launcher.run_session(&adb))
+        });
+        self.sessions.lock().unwrap().push(Session {
+            id,
+            profile,
+            serial,
+            stopper,
+            end,
+            perf,
+            thread,
+        });
+        Ok(id)
+    }
+
+    /// Stops the running sessions and waits for them and their post-exit
+    /// hooks to finish, so none outlives the wrapper.
+    pub fn shutdown(&self) {
+        let sessions = std::mem::take(&mut *self.sessions.lock().unwrap());
+        for session in &sessions {
+            session.stopper.stop();
+        }
+        for session in sessions {
+            let _ = session.thread.join();
+        }
+    }
+
+    /// Listens on `path` until the listener fails, serving every
+    /// connection on its own thread. A stale socket left behind by a
+    /// previous run is replaced; any other file there is left alone.
+    #[cfg(unix)]
+    pub fn serve(self: Arc<Self>, path: &Path) -> io::Result<()> {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use", path.display()),
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        // SAFETY: umask(2) only swaps the process's file mode mask. The
+        // socket is created owner-only, so no other user can connect before
+        // its permissions would be changed; files other threads create
+        // meanwhile come out owner-only too.
+        let mask = unsafe { libc::umask(0o177) };
+        let listener = UnixListener::bind(path);
+        unsafe { libc::umask(mask) };
+        let listener = listener?;
+        log(&format!("control API listening on {}", path.display()));
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            thread::spawn(move || -> io::Result<()> {
+                let mut writer = stream.try_clone()?;
+                for line in BufReader::new(stream).lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        writeln!(writer, "{}", server.handle(&line))?;
+                    }
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn serve(self: Arc<Self>, _path: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the control API needs Unix domain sockets",
+        ))
+    }
+}
+
+/// Forgets the `ended` sessions, joining their threads.
+fn prune(sessions: &mut Vec<Session>, ended: impl Fn(&Session) -> bool) {
+    for session in sessions.extract_if(.., |s| ended(s)) {
+        let _ = session.thread.join();
+    }
+}
+
+/// The config file with the installs detected, which scans the filesystem.
+fn load_config() -> Config {
+    let mut config = ConfigRaw::load().unwrap_or_default().to_config();
+    config.detect_installs();
+    config
+}
+
+/// The version of the profile's scrcpy, detected per request since
+/// profiles may point at different installs.
+fn scrcpy_info(item: &ConfigItem) -> Option<ScrcpyInfo> {
+    item.executable.as_deref().and_then(ScrcpyInfo::detect)
+}
+
+/// The profile named by the request, or the default config, with the
+/// request's serial if it has one.
+fn profile(target: Target, config: &Config) -> Result<(Option<String>, ConfigItem), String> {
+    let mut item = match &target.profile {
+        Some(name) => config
+            .saved
+            .get(name)
+            .ok_or(format!("unknown profile {name}"))?
+            .clone(),
+        None => config.default.clone(),
+    };
+    if let Some(serial) = target.serial {
+        item.serial = serial;
+    }
+    Ok((target.profile, item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = ConfigRaw::default().to_config();
+        let mut phone = config.default.clone();
+        phone.executable = Some("scrcpy".to_string());
+        phone.fps = Some(30);
+        config.saved.insert("phone".to_string(), phone.clone());
+        config.saved.insert("car".to_string(), phone);
+        config
+    }
+
+    fn respond(line: &str) -> String {
+        ControlServer::new(config()).handle(line)
+    }
+
+    #[test]
+    fn lists_profiles() {
+        assert_eq!(
+            respond(r#"{"cmd":"list_profiles"}"#),
+            r#"{"ok":true,"profiles":["car","phone"]}"#
+        );
+    }
+
+    #[test]
+    fn get_args_uses_profile_and_serial() {
+        let response: serde_json::Value = serde_json::from_str(&respond(
+            r#"{"cmd":"get_args","profile":"phone","serial":"R58M12345"}"#,
+        ))
+        .unwrap();
+        let args = response["args"].as_str().unwrap();
+        assert!(args.contains("--serial=R58M12345"), "{args}");
+        assert!(args.contains("--max-fps=30"), "{args}");
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            respond(r#"{"cmd":"get_args","profile":"tv"}"#),
+            r#"{"ok":false,"error":"unknown profile tv"}"#
+        );
+        assert_eq!(
+            respond(r#"{"cmd":"stop","session":7}"#),
+            r#"{"ok":false,"error":"unknown session 7"}"#
+        );
+        assert!(respond(r#"{"cmd":"reboot"}"#)
+            .starts_with(r#"{"ok":false,"error":"unknown variant `reboot`"#));
+        assert!(respond("not json").starts_with(r#"{"ok":false,"#));
+    }
+
+    #[test]
+    fn reports_status() {
+        assert_eq!(
+            respond(r#"{"cmd":"status"}"#),
+            r#"{"ok":true,"sessions":[]}"#
+        );
+    }
+
+    #[test]
+    fn forgets_ended_sessions() {
+        let server = ControlServer::new(config());
+        for (id, end) in [
+            (1, None),
+            (2, Some(SessionEnd::Exited(Some(0)))),
+            (3, None),
+            (4, Some(SessionEnd::Stopped)),
+        ] {
+            server.sessions.lock().unwrap().push(Session {
+                id,
+                profile: None,
+                serial: String::new(),
+                stopper: Stopper::default(),
+                end: Arc::new(Mutex::new(end)),
+                perf: Arc::default(),
+                thread: thread::spawn(|| {}),
+            });
+        }
+        let states = || -> Vec<(u64, String)> {
+            let status = server.handle(r#"{"cmd":"status"}"#);
+            let status: serde_json::Value = serde_json::from_str(&status).unwrap();
+            status["sessions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| {
+                    (
+                        s["session"].as_u64().unwrap(),
+                        s["state"].as_str().unwrap().into(),
+                    )
+                })
+                .collect()
+        };
+        let running = |id| (id, "running".to_string());
+        assert_eq!(
+            states(),
+            [
+                running(1),
+                (2, "exited".to_string()),
+                running(3),
+                (4, "stopped".to_string())
+            ]
+        );
+        assert_eq!(states(), [running(1), running(3)]);
+
+        *server.sessions.lock().unwrap()[0].end.lock().unwrap() = Some(SessionEnd::Stopped);
+        // fails for the default config without an executable, after pruning
+        assert!(server
+            .handle(r#"{"cmd":"launch"}"#)
+            .contains("no scrcpy executable"));
+        let sessions = server.sessions.lock().unwrap();
+        assert_eq!(sessions.iter().map(|s| s.id).collect::<Vec<_>>(), [3]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!(
+            "scrcpy-wrapper-private-{}.sock",
+            std::process::id()
+        ));
+        let server = Arc::new(ControlServer::new(config()));
+        thread::spawn({
+            let path = path.clone();
+            move || server.serve(&path)
+        });
+        let mut metadata = std::fs::metadata(&path);
+        for _ in 0..100 {
+            if metadata.is_ok() {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(20));
+            metadata = std::fs::metadata(&path);
+        }
+        assert_eq!(metadata.unwrap().permissions().mode() & 0o777, 0o600);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serve_leaves_other_files_alone() {
+        let path = std::env::temp_dir().join(format!("scrcpy-wrapper-{}.sock", std::process::id()));
+        std::fs::write(&path, "notes").unwrap();
+        let error = Arc::new(ControlServer::new(config()))
+            .serve(&path)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+use crate::config::{AudioCodec, AudioSource, BitRate, ConfigItem, VideoSource};
+
+/// The bandwidth a session is expected to use in bits per second: its
+/// video and audio bit rates, or scrcpy's defaults where blank. Raw and
+/// FLAC audio ignore the bit rate and are counted as uncompressed.
+pub fn estimated_bandwidth(config: &ConfigItem) -> u64 {
+    let video = if config.video_source == VideoSource::No {
+        0
+    } else {
+        config
+            .options
+            .video_bit_rate
+            .unwrap_or(BitRate::DEFAULT_VIDEO)
+            .bits_per_second()
+    };
+    let audio = match config.audio_codec {
+        _ if config.audio_source == AudioSource::No => 0,
+        AudioCodec::Raw | AudioCodec::Flac => BitRate::RAW_AUDIO.bits_per_second(),
+        AudioCodec::Opus | AudioCodec::Aac => config
+            .options
+            .audio_bit_rate
+            .unwrap_or(BitRate::DEFAULT_AUDIO)
+            .bits_per_second(),
+    };
+    u64::from(video) + u64::from(audio)
+}
+
+/// Bytes recorded over `seconds` at the [`estimated_bandwidth`].
+pub fn estimated_size(config: &ConfigItem, seconds: u64) -> u64 {
+    estimated_bandwidth(config) * seconds / 8
+}
+
+/// e.g. `8.13 Mbps`.
+pub fn format_bandwidth(bits_per_second: u64) -> String {
+    format!("{:.2} Mbps", bits_per_second as f64 / 1e6)
+}
+
+/// e.g. `61.0 MB`, in powers of 1000.
+pub fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KB", "MB", "GB"] {
+        if size < 1000.0 {
+            return format!("{size:.1} {unit}");
+        }
+        size /= 1000.0;
+    }
+    format!("{size:.1} TB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigItemRaw;
+
+    #[test]
+    fn estimates_from_bit_rates() {
+        let mut config = ConfigItemRaw::default().to_config();
+        assert_eq!(estimated_bandwidth(&config), 8_128_000);
+
+        config.options.video_bit_rate = BitRate::new(4_000_000);
+        config.options.audio_bit_rate = BitRate::new(64_000);
+        assert_eq!(estimated_bandwidth(&config), 4_064_000);
+        assert_eq!(estimated_size(&config, 60), 30_480_000);
+        assert_eq!(format_size(30_480_000), "30.5 MB");
+
+        config.audio_codec = AudioCodec::Raw;
+        assert_eq!(estimated_bandwidth(&config), 5_536_000);
+        config.video_source = VideoSource::No;
+        assert_eq!(format_bandwidth(estimated_bandwidth(&config)), "1.54 Mbps");
+    }
+}
@@ -0,0 +1,308 @@
+use crate::config::Config;
+use crate::util::{build_args, child_env, ScrcpyInfo};
+use crate::{config_enum, t, Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+config_enum! {
+    pub enum LauncherFormat {
+        #[cfg_attr(not(any(windows, target_os = "macos")), default)]
+        Desktop: "desktop", t! {zh: "桌面快捷方式 (.desktop)", en: "desktop entry (.desktop)"}.to_string(),
+        #[cfg_attr(target_os = "macos", default)]
+        Shell: "sh", t! {zh: "Shell 脚本 (.sh)", en: "shell script (.sh)"}.to_string(),
+        #[cfg_attr(windows, default)]
+        Batch: "bat", t! {zh: "批处理文件 (.bat)", en: "batch file (.bat)"}.to_string(),
+    }
+}
+
+/// What a launcher runs: the command line and the environment it sets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LauncherCommand {
+    pub argv: Vec<String>,
+    pub env: Vec<(String, String)>,
+}
+
+/// The command line a launcher for `profile` runs: `wrapper run <profile>`,
+/// which follows later edits of the profile and runs its hooks, or else
+/// scrcpy itself with the profile's current arguments and environment,
+/// leaving out the options the scrcpy described by `info` lacks.
+pub fn launcher_command(
+    profile: &str,
+    config: &Config,
+    info: Option<&ScrcpyInfo>,
+    wrapper: Option<&Path>,
+) -> Result<LauncherCommand> {
+    let Some(item) = config.saved.get(profile) else {
+        return Err(Error::Launcher(
+            t! {en: "unknown profile {}", zh: "未知的配置 {}", (profile)}.to_string(),
+        ));
+    };
+    if let Some(wrapper) = wrapper {
+        let argv = vec![
+            wrapper.to_string_lossy().into_owned(),
+            "run".to_string(),
+            profile.to_string(),
+        ];
+        return Ok(LauncherCommand { argv, env: vec![] });
+    }
+    let executable = item
+        .executable
+        .clone()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "scrcpy".to_string());
+    let args = shell_words::split(&build_args(item, info)).map_err(|e| {
+        Error::Launcher(t! {en: "invalid arguments: {}", zh: "参数无效：{}", (e)}.to_string())
+    })?;
+    let env = child_env(config.adb(), item);
+    if let Some((key, _)) = env.iter().find(|(key, _)| !is_env_name(key)) {
+        return Err(Error::Launcher(
+            t! {
+                en: "{:?} is not a valid environment variable name",
+                zh: "{:?} 不是有效的环境变量名",
+                (key)
+            }
+            .to_string(),
+        ));
+    }
+    let argv = std::iter::once(executable).chain(args).collect();
+    Ok(LauncherCommand { argv, env })
+}
+
+/// Whether `key` is a portable environment variable name, one that every
+/// launcher format can set: `[A-Za-z_][A-Za-z0-9_]*`.
+fn is_env_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A POSIX `sh` word.
+fn sh_quote(arg: &str) -> String {
+    shell_words::quote(arg).into_owned()
+}
+
+/// An argument of a desktop entry's `Exec` key, escaped for the key's
+/// string value as well.
+fn desktop_quote(arg: &str) -> String {
+    const RESERVED: &str = " \t\n\"'\\><~|&;$*?#()`";
+    let arg = arg.replace('%', "%%");
+    if arg.is_empty() || arg.contains(|c| RESERVED.contains(c)) {
+        let mut quoted = String::from("\"");
+        for c in arg.chars() {
+            if "\"`$\\".contains(c) {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted.replace('\\', "\\\\")
+    } else {
+        arg
+    }
+}
+
+/// An argument of a `cmd` batch file. A quote inside is doubled, which
+/// both `cmd` and the C runtime's argument parser read as a literal quote.
+fn bat_quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || "&|<>^()\"".contains(c)) {
+        format!("\"{}\"", arg.replace('"', "\"\""))
+    } else {
+        arg
+    }
+}
+
+/// The launcher file's content for `argv` with `env` set.
+pub fn launcher_script(
+    format: LauncherFormat,
+    profile: &str,
+    argv: &[String],
+    env: &[(String, String)],
+) -> String {
+    match format {
+        LauncherFormat::Desktop => {
+            let mut exec: Vec<String> = vec![];
+            if !env.is_empty() {
+                exec.push("env".to_string());
+                exec.extend(env.iter().map(|(k, v)| desktop_quote(&format!("{k}={v}"))));
+            }
+            exec.extend(argv.iter().map(|arg| desktop_quote(arg)));
+            let name = profile.replace('\\', "\\\\").replace('\n', " ");
+            format!(
+                "[Desktop Entry]\n\
+                 Type=Application\n\
+                 Name=scrcpy ({name})\n\
+                 Comment=Mirror an Android device with scrcpy\n\
+                 Exec={}\n\
+                 Icon=scrcpy\n\
+                 Terminal=false\n\
+                 Categories=Utility;\n",
+                exec.join(" ")
+            )
+        }
+        LauncherFormat::Shell => {
+            let mut script = format!("#!/bin/sh\n# scrcpy profile {}\n", sh_quote(profile));
+            for (key, value) in env {
+                script.push_str(&format!("export {key}={}\n", sh_quote(value)));
+            }
+            let argv: Vec<_> = argv.iter().map(|arg| sh_quote(arg)).collect();
+            script.push_str(&format!("exec {} \"$@\"\n", argv.join(" ")));
+            script
+        }
+        LauncherFormat::Batch => {
+            let mut script = format!("@echo off\r\nrem scrcpy profile {}\r\n", bat_quote(profile));
+            for (key, value) in env {
+                script.push_str(&format!("set \"{key}={}\"\r\n", value.replace('%', "%%")));
+            }
+            let argv: Vec<_> = argv.iter().map(|arg| bat_quote(arg)).collect();
+            script.push_str(&format!("{} %*\r\n", argv.join(" ")));
+            script
+        }
+    }
+}
+
+/// Where a launcher for `profile` goes: desktop entries into
+/// `$XDG_DATA_HOME/applications` (`~/.local/share/applications`), scripts
+/// into the home directory.
+pub fn launcher_path(format: LauncherFormat, profile: &str) -> PathBuf {
+    let home = home::home_dir().unwrap_or_default();
+    let name: String = profile
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    match format {
+        LauncherFormat::Desktop => std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .unwrap_or_else(|| home.join(".local").join("share"))
+            .join("applications")
+            .join(format!("scrcpy-wrapper-{name}.desktop")),
+        LauncherFormat::Shell => home.join(format!("scrcpy-{name}.sh")),
+        LauncherFormat::Batch => home.join(format!("scrcpy-{name}.bat")),
+    }
+}
+
+/// Writes a launcher for the saved `profile`, see [`launcher_command`],
+/// and returns its path.
+pub fn export_launcher(
+    format: LauncherFormat,
+    profile: &str,
+    config: &Config,
+    info: Option<&ScrcpyInfo>,
+    wrapper: Option<&Path>,
+) -> Result<PathBuf> {
+    let LauncherCommand { argv, env } = launcher_command(profile, config, info, wrapper)?;
+    let path = launcher_path(format, profile);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, launcher_script(format, profile, &argv, &env))?;
+    #[cfg(unix)]
+    if format != LauncherFormat::Batch {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv() -> Vec<String> {
+        [
+            "/opt/scrcpy dir/scrcpy",
+            "--window-title=Mom's \"tab\"",
+            "--max-fps=30",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn env() -> Vec<(String, String)> {
+        vec![("ADB".to_string(), "/opt/adb 100%".to_string())]
+    }
+
+    #[test]
+    fn shell_script_round_trips() {
+        let script = launcher_script(LauncherFormat::Shell, "tv", &argv(), &env());
+        let exec = script.lines().last().unwrap();
+        let words = shell_words::split(exec).unwrap();
+        assert_eq!(words[0], "exec");
+        assert_eq!(words[1..4], argv());
+        assert!(script.contains("export ADB='/opt/adb 100%'\n"), "{script}");
+    }
+
+    #[test]
+    fn desktop_entry_escapes_exec() {
+        let entry = launcher_script(LauncherFormat::Desktop, "tv", &argv(), &env());
+        let exec = entry.lines().find(|l| l.starts_with("Exec=")).unwrap();
+        assert_eq!(
+            exec,
+            r#"Exec=env "ADB=/opt/adb 100%%" "/opt/scrcpy dir/scrcpy" "--window-title=Mom's \\"tab\\"" --max-fps=30"#
+        );
+    }
+
+    #[test]
+    fn batch_file_quotes_args() {
+        let script = launcher_script(LauncherFormat::Batch, "tv", &argv(), &env());
+        assert!(
+            script.contains("set \"ADB=/opt/adb 100%%\"\r\n"),
+            "{script}"
+        );
+        assert!(script.ends_with(
+            "\"/opt/scrcpy dir/scrcpy\" \"--window-title=Mom's \"\"tab\"\"\" --max-fps=30 %*\r\n"
+        ));
+    }
+
+    fn config() -> Config {
+        let mut config = crate::config::ConfigRaw::default().to_config();
+        let mut tv = config.default.clone();
+        tv.executable = Some("/opt/scrcpy".to_string());
+        config.saved.insert("tv".to_string(), tv);
+        config
+    }
+
+    #[test]
+    fn wrapper_launchers_run_the_profile() {
+        let wrapper = Path::new("/usr/bin/scrcpy-wrapper");
+        let LauncherCommand { argv, env } =
+            launcher_command("tv", &config(), None, Some(wrapper)).unwrap();
+        assert_eq!(argv, ["/usr/bin/scrcpy-wrapper", "run", "tv"]);
+        assert!(env.is_empty());
+    }
+
+    #[test]
+    fn refuses_what_a_launcher_cant_express() {
+        assert!(launcher_command("radio", &config(), None, None).is_err());
+
+        let mut config = config();
+        let tv = config.saved.get_mut("tv").unwrap();
+        tv.env = vec![("SCRCPY_SERVER_PATH".into(), "/opt/server".into())];
+        let LauncherCommand { argv, env } = launcher_command("tv", &config, None, None).unwrap();
+        assert_eq!(argv[0], "/opt/scrcpy");
+        assert!(env.contains(&("SCRCPY_SERVER_PATH".into(), "/opt/server".into())));
+
+        for key in ["1ST", "MY-VAR", "A B", "X=Y"] {
+            let tv = config.saved.get_mut("tv").unwrap();
+            tv.env = vec![(key.into(), "1".into())];
+            assert!(
+                launcher_command("tv", &config, None, None).is_err(),
+                "{key}"
+            );
+        }
+
+        let tv = config.saved.get_mut("tv").unwrap();
+        tv.env = vec![];
+        tv.additional_args = "--window-title='unclosed".into();
+        assert!(launcher_command("tv", &config, None, None).is_err());
+    }
+}
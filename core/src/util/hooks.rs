@@ -0,0 +1,219 @@
+use crate::config::{ConfigItem, HookFailure};
+use crate::util::log;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+fn shell(command: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::process::CommandExt;
+        let mut shell = Command::new("sh");
+        // a group of its own, for `kill_hook` to end what it started
+        shell.args(["-c", command]).process_group(0);
+        shell
+    }
+}
+
+/// Kills a hook that timed out along with the processes it started, which
+/// killing the shell alone would leave running.
+fn kill_hook(child: &mut Child) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    #[cfg(not(target_os = "windows"))]
+    // SAFETY: kill(2) only sends a signal; a negative pid addresses the
+    // process group `shell` put the hook in.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// `env` plus variables describing the session, passed to both hooks.
+fn hook_env(
+    config: &ConfigItem,
+    profile: Option<&str>,
+    env: &[(String, String)],
+) -> Vec<(String, String)> {
+    let session = [
+        ("SCRCPY_SERIAL", config.serial.clone()),
+        ("SCRCPY_PROFILE", profile.unwrap_or_default().to_string()),
+        (
+            "SCRCPY_EXECUTABLE",
+            config.executable.clone().unwrap_or_default(),
+        ),
+        (
+            "SCRCPY_RECORD_PATH",
+            config.options.record.trim().to_string(),
+        ),
+        ("SCRCPY_V4L2_SINK", config.options.v4l2.trim().to_string()),
+    ];
+    env.iter()
+        .cloned()
+        .chain(session.map(|(key, value)| (key.to_string(), value)))
+        .collect()
+}
+
+/// Runs `command` through the shell, killing it after `timeout`.
+fn run_hook(command: &str, env: &[(String, String)], timeout: Duration) -> Result<(), String> {
+    let mut child = shell(command)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(format!("exited with {status}"))
+            };
+        }
+        if started.elapsed() >= timeout {
+            kill_hook(&mut child);
+            return Err(format!("timed out after {}s", timeout.as_secs()));
+        }
+        sleep(Duration::from_millis(100));
+    }
+}
+
+/// Runs the pre-launch hook, returning whether scrcpy should be started.
+pub fn run_pre_launch_hook(
+    config: &ConfigItem,
+    profile: Option<&str>,
+    env: &[(String, String)],
+) -> bool {
+    let command = config.pre_launch_hook.trim();
+    if command.is_empty() {
+        return true;
+    }
+    log(&format!("running pre-launch hook: {command}"));
+    let timeout = Duration::from_secs(config.hook_timeout.get().into());
+    match run_hook(command, &hook_env(config, profile, env), timeout) {
+        Ok(()) => true,
+        Err(e) if config.hook_failure == HookFailure::Abort => {
+            log(&format!("pre-launch hook {e}, aborting launch"));
+            false
+        }
+        Err(e) => {
+            log(&format!("pre-launch hook {e}, launching anyway"));
+            true
+        }
+    }
+}
+
+/// Runs the post-exit hook with `SCRCPY_EXIT_CODE` (and `SCRCPY_EXIT_SIGNAL`
+/// on Unix) set from the last session. Failures are only logged.
+pub fn run_post_exit_hook(
+    config: &ConfigItem,
+    profile: Option<&str>,
+    env: &[(String, String)],
+    status: &ExitStatus,
+) {
+    let command = config.post_exit_hook.trim();
+    if command.is_empty() {
+        return;
+    }
+    let mut env = hook_env(config, profile, env);
+    env.push((
+        "SCRCPY_EXIT_CODE".to_string(),
+        status.code().map(|c| c.to_string()).unwrap_or_default(),
+    ));
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(status) {
+        env.push(("SCRCPY_EXIT_SIGNAL".to_string(), signal.to_string()));
+    }
+    log(&format!("running post-exit hook: {command}"));
+    let timeout = Duration::from_secs(config.hook_timeout.get().into());
+    if let Err(e) = run_hook(command, &env, timeout) {
+        log(&format!("post-exit hook {e}"));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::config::ConfigItemRaw;
+
+    fn config(hook: &str, failure: HookFailure) -> ConfigItem {
+        let mut config = ConfigItemRaw::default().to_config();
+        config.pre_launch_hook = hook.to_string();
+        config.hook_failure = failure;
+        config
+    }
+
+    #[test]
+    fn successful_hook_launches() {
+        assert_eq!(run_hook("true", &[], Duration::from_secs(5)), Ok(()));
+        assert!(run_pre_launch_hook(
+            &config("true", HookFailure::Abort),
+            None,
+            &[]
+        ));
+    }
+
+    #[test]
+    fn failing_hook_aborts_unless_told_to_warn() {
+        assert_eq!(
+            run_hook("exit 3", &[], Duration::from_secs(5)),
+            Err("exited with exit status: 3".to_string())
+        );
+        assert!(!run_pre_launch_hook(
+            &config("exit 3", HookFailure::Abort),
+            None,
+            &[]
+        ));
+        assert!(run_pre_launch_hook(
+            &config("exit 3", HookFailure::Warn),
+            None,
+            &[]
+        ));
+    }
+
+    #[test]
+    fn timeout_kills_what_the_hook_started() {
+        let pid_file = std::env::temp_dir().join(format!("hook-test-{}", std::process::id()));
+        let command = format!("sleep 30 & echo $! > '{}'; wait", pid_file.display());
+        let result = run_hook(&command, &[], Duration::from_millis(500));
+        assert_eq!(result, Err("timed out after 0s".to_string()));
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let _ = std::fs::remove_file(&pid_file);
+        let alive = |pid: &str| {
+            Command::new("kill")
+                .args(["-0", pid.trim()])
+                .stderr(Stdio::null())
+                .status()
+                .unwrap()
+                .success()
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while alive(&pid) {
+            assert!(Instant::now() < deadline, "sleep {pid} outlived the hook");
+            sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn hooks_see_the_session_and_profile_env() {
+        let hook = r#"test "$SCRCPY_SERIAL" = R58M && test "$SCRCPY_PROFILE" = phone && test "$FOO" = bar"#;
+        let mut config = config(hook, HookFailure::Abort);
+        config.serial = "R58M".to_string();
+        let env = [("FOO".to_string(), "bar".to_string())];
+        assert!(run_pre_launch_hook(&config, Some("phone"), &env));
+        assert!(!run_pre_launch_hook(&config, None, &env));
+    }
+}
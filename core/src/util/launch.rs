@@ -0,0 +1,427 @@
+use crate::config::{
+    unix_now, AudioCodec, ConfigEnum, ConfigItem, ConnectMethod, History, SessionRecord,
+    VideoCodec, LOG_TAIL_LINES,
+};
+use crate::util::args::quote;
+use crate::util::{
+    run_post_exit_hook, run_pre_launch_hook, supervise, Adb, PerfEvent, PerfMonitor,
+};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+/// Writes a line to the session log, i.e. the wrapper's stderr alongside
+/// the output of scrcpy.
+pub fn log(message: &str) {
+    eprintln!("[scrcpy-wrapper] {message}");
+}
+
+/// Environment for scrcpy and the hooks: `ADB` from the global adb path,
+/// then the profile's variables, which may override it.
+pub fn child_env(adb: Option<&str>, config: &ConfigItem) -> Vec<(String, String)> {
+    adb.filter(|adb| !adb.trim().is_empty())
+        .map(|adb| ("ADB", adb))
+        .into_iter()
+        .chain(config.env_vars())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CodecFailure {
+    Video,
+    Audio,
+}
+
+impl CodecFailure {
+    /// Recognizes encoder errors in the scrcpy log, e.g.
+    /// `ERROR: Could not create default video encoder for av1` or
+    /// `ERROR: Encoder 'c2.android.opus.encoder' for 'opus' not found`.
+    pub fn detect(line: &str, config: &ConfigItem) -> Option<Self> {
+        let line = line.to_lowercase();
+        if !line.contains("encoder") && !line.contains("codec") {
+            return None;
+        }
+        if !["could not", "not found", "failed", "unsupported"]
+            .iter()
+            .any(|e| line.contains(e))
+        {
+            return None;
+        }
+        if line.contains("video") || line.contains(&config.video_codec.to_config_string()) {
+            Some(Self::Video)
+        } else if line.contains("audio") || line.contains(&config.audio_codec.to_config_string()) {
+            Some(Self::Audio)
+        } else {
+            None
+        }
+    }
+}
+
+/// Feeds a line of the scrcpy log to the FPS monitor, logging the
+/// statistics every few samples.
+fn monitor(perf: &Mutex<PerfMonitor>, line: &str) {
+    let mut perf = perf.lock().unwrap();
+    if let Some(PerfEvent::Fps { .. }) = perf.record(line) {
+        if let Some(line) = perf.live_line() {
+            log(&line);
+        }
+    }
+}
+
+/// Sets `option` to `value` in a command line, replacing every occurrence
+/// (`--option=value` or `--option value`) or appending it. `None` if the
+/// command line can't be parsed.
+fn set_option(args: &str, option: &str, value: &str) -> Option<String> {
+    let tokens = shell_words::split(args).ok()?;
+    let prefix = format!("{option}=");
+    let mut kept = Vec::with_capacity(tokens.len() + 1);
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        if token == option {
+            tokens.next();
+        } else if !token.starts_with(&prefix) {
+            kept.push(token);
+        }
+    }
+    kept.push(format!("{option}={value}"));
+    Some(kept.iter().map(|t| quote(t)).collect::<Vec<_>>().join(" "))
+}
+
+/// Stops a [`Launcher`] from another thread: kills the running scrcpy and
+/// keeps the codec fallback from starting it again.
+#[derive(Debug, Clone, Default)]
+pub struct Stopper {
+    child: Arc<Mutex<Option<Child>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Stopper {
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            let _ = child.kill();
+        }
+    }
+
+    pub fn stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+}
+
+/// How a session run by [`Launcher::run_session`] ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEnd {
+    Exited(Option<i32>),
+    Stopped,
+    Failed(String),
+}
+
+/// Runs scrcpy for a profile, moving down its codec fallback lists while
+/// an encoder fails to initialize. The chosen codecs are kept across runs,
+/// and every run is recorded in the [`History`].
+pub struct Launcher {
+    executable: String,
+    args: String,
+    config: ConfigItem,
+    profile: Option<String>,
+    env: Vec<(String, String)>,
+    video_fallback: VecDeque<VideoCodec>,
+    audio_fallback: VecDeque<AudioCodec>,
+    fell_back: bool,
+    stopper: Stopper,
+    perf: Arc<Mutex<PerfMonitor>>,
+}
+
+impl Launcher {
+    pub fn new(
+        executable: String,
+        args: String,
+        config: ConfigItem,
+        profile: Option<String>,
+        env: Vec<(String, String)>,
+    ) -> Self {
+        let video_fallback = config
+            .video_codec_fallback
+            .iter()
+            .filter(|c| **c != config.video_codec)
+            .copied()
+            .collect();
+        let audio_fallback = config
+            .audio_codec_fallback
+            .iter()
+            .filter(|c| **c != config.audio_codec)
+            .copied()
+            .collect();
+        Self {
+            executable,
+            args,
+            config,
+            profile,
+            env,
+            video_fallback,
+            audio_fallback,
+            fell_back: false,
+            stopper: Stopper::default(),
+            perf: Arc::default(),
+        }
+    }
+
+    pub fn stopper(&self) -> Stopper {
+        self.stopper.clone()
+    }
+
+    /// The performance of the current run, updated while scrcpy prints it.
+    pub fn perf(&self) -> Arc<Mutex<PerfMonitor>> {
+        self.perf.clone()
+    }
+
+    pub fn run(&mut self) -> io::Result<ExitStatus> {
+        loop {
+            let (status, failure) = self.run_once()?;
+            let failure = failure.filter(|_| !status.success() && !self.stopper.stopped());
+            let Some(failure) = failure else {
+                if self.fell_back {
+                    log(&format!(
+                        "session ran with video codec {} and audio codec {}",
+                        self.config.video_codec, self.config.audio_codec
+                    ));
+                }
+                return Ok(status);
+            };
+            if !self.fall_back(failure) {
+                log("encoder failed and no fallback codec is left");
+                return Ok(status);
+            }
+        }
+    }
+
+    /// [`run`](Self::run), supervised when the profile reconnects
+    /// automatically over adb. Supervision ends with an `Interrupted` error
+    /// once stopped.
+    pub fn run_supervised(&mut self, adb: &Adb) -> io::Result<ExitStatus> {
+        if !self.config.auto_reconnect || self.config.connect_method != ConnectMethod::Adb {
+            return self.run();
+        }
+        let serial = self.config.serial.clone();
+        let retries = self.config.reconnect_retries;
+        let stopper = self.stopper();
+        let launch = || {
+            let status = self.run()?;
+            if stopper.stopped() {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "stopped"));
+            }
+            Ok(status)
+        };
+        supervise(launch, adb, &serial, retries, &stopper)
+    }
+
+    /// A whole session: the pre-launch hook, scrcpy as in
+    /// [`run_supervised`](Self::run_supervised), then the post-exit hook.
+    pub fn run_session(&mut self, adb: &Adb) -> SessionEnd {
+        let config = self.config.clone();
+        let (profile, env) = (self.profile.clone(), self.env.clone());
+        if !run_pre_launch_hook(&config, profile.as_deref(), &env) {
+            return SessionEnd::Failed("pre-launch hook failed".to_string());
+        }
+        match self.run_supervised(adb) {
+            Ok(status) => {
+                run_post_exit_hook(&config, profile.as_deref(), &env, &status);
+                if self.stopper.stopped() {
+                    SessionEnd::Stopped
+                } else {
+                    SessionEnd::Exited(status.code())
+                }
+            }
+            Err(_) if self.stopper.stopped() => SessionEnd::Stopped,
+            Err(e) => SessionEnd::Failed(e.to_string()),
+        }
+    }
+
+    /// Switches to the next candidate for the failed stream. Only the codec
+    /// option of the command line changes, as it may have been edited.
+    fn fall_back(&mut self, failure: CodecFailure) -> bool {
+        let (option, codec) = match failure {
+            CodecFailure::Video => {
+                let Some(codec) = self.video_fallback.pop_front() else {
+                    return false;
+                };
+                log(&format!(
+                    "video codec {} failed, retrying with {codec}",
+                    self.config.video_codec
+                ));
+                self.config.video_codec = codec;
+                ("--video-codec", codec.to_config_string())
+            }
+            CodecFailure::Audio => {
+                let Some(codec) = self.audio_fallback.pop_front() else {
+                    return false;
+                };
+                log(&format!(
+                    "audio codec {} failed, retrying with {codec}",
+                    self.config.audio_codec
+                ));
+                self.config.audio_codec = codec;
+                ("--audio-codec", codec.to_config_string())
+            }
+        };
+        let Some(args) = set_option(&self.args, option, &codec) else {
+            log("the command line can't be parsed, not falling back");
+            return false;
+        };
+        self.args = args;
+        self.fell_back = true;
+        true
+    }
+
+    /// Runs scrcpy once, echoing its output, watching stderr for encoder
+    /// errors and both streams for performance figures.
+    fn run_once(&self) -> io::Result<(ExitStatus, Option<CodecFailure>)> {
+        let args = if self.args.trim().is_empty() {
+            vec![]
+        } else {
+            shell_words::split(&self.args).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid arguments: {e}"),
+                )
+            })?
+        };
+
+        let timestamp = unix_now();
+        let started = Instant::now();
+        let mut child = Command::new(&self.executable)
+            .args(&args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stderr = child.stderr.take();
+        *self.perf.lock().unwrap() = PerfMonitor::default();
+        let stdout = child.stdout.take().map(|stdout| {
+            let perf = self.perf.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    println!("{line}");
+                    monitor(&perf, &line);
+                }
+            })
+        });
+        *self.stopper.child.lock().unwrap() = Some(child);
+        if self.stopper.stopped() {
+            self.stopper.stop();
+        }
+
+        let mut failure = None;
+        let mut log_tail = VecDeque::with_capacity(LOG_TAIL_LINES);
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                eprintln!("{line}");
+                monitor(&self.perf, &line);
+                failure = failure.or_else(|| CodecFailure::detect(&line, &self.config));
+                if log_tail.len() == LOG_TAIL_LINES {
+                    log_tail.pop_front();
+                }
+                log_tail.push_back(line);
+            }
+        }
+        if let Some(stdout) = stdout {
+            let _ = stdout.join();
+        }
+        let child = self.stopper.child.lock().unwrap().take();
+        let status = child
+            .ok_or_else(|| io::Error::other("scrcpy process went missing"))?
+            .wait()?;
+
+        let perf = if self.config.options.print_fps {
+            self.perf.lock().unwrap().summary()
+        } else {
+            None
+        };
+        if let Some(perf) = &perf {
+            log(&format!("session {perf}"));
+        }
+
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        let record = SessionRecord {
+            timestamp,
+            profile: self.profile.clone(),
+            serial: self.config.serial.clone(),
+            argv: std::iter::once(self.executable.clone())
+                .chain(args)
+                .collect(),
+            exit_code: status.code(),
+            signal,
+            duration: started.elapsed().as_secs(),
+            log_tail: if status.success() {
+                vec![]
+            } else {
+                log_tail.into()
+            },
+            config: Some(self.config.to_raw()),
+            perf,
+        };
+        if let Err(e) = History::append(record) {
+            log(&format!("failed to write session history: {e}"));
+        }
+
+        Ok((status, failure))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_keeps_the_edited_command_line() {
+        assert_eq!(
+            set_option(
+                "--video-codec=av1 --window-title='My phone' --no-audio",
+                "--video-codec",
+                "h265"
+            )
+            .as_deref(),
+            Some("'--window-title=My phone' --no-audio --video-codec=h265")
+        );
+        assert_eq!(
+            set_option("--audio-codec aac -s R58M1", "--audio-codec", "opus").as_deref(),
+            Some("-s R58M1 --audio-codec=opus")
+        );
+        assert_eq!(
+            set_option("--max-fps=60", "--video-codec", "h264").as_deref(),
+            Some("--max-fps=60 --video-codec=h264")
+        );
+        assert_eq!(
+            set_option("--video-codec-options=profile=1", "--video-codec", "h265").as_deref(),
+            Some("--video-codec-options=profile=1 --video-codec=h265")
+        );
+        assert_eq!(
+            set_option("--window-title='unclosed", "--video-codec", "h265"),
+            None
+        );
+    }
+
+    #[test]
+    fn unparsable_arguments_are_an_error() {
+        let config = crate::config::ConfigItemRaw::default().to_config();
+        let mut launcher = Launcher::new(
+            "scrcpy".into(),
+            "--window-title='unclosed".into(),
+            config,
+            None,
+            vec![],
+        );
+        let error = launcher.run().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,25 @@
+mod adb;
+mod apps;
+mod args;
+mod control;
+mod estimate;
+mod export;
+mod hooks;
+mod launch;
+mod perf;
+mod supervisor;
+mod validate;
+mod version;
+
+pub use adb::*;
+pub use apps::*;
+pub use args::*;
+pub use control::*;
+pub use estimate::*;
+pub use export::*;
+pub use hooks::*;
+pub use launch::*;
+pub use perf::*;
+pub use supervisor::*;
+pub use validate::*;
+pub use version::*;
@@ -0,0 +1,180 @@
+use crate::config::PerfSummary;
+
+/// Points kept in a session's FPS chart; longer sessions are averaged down.
+pub const CHART_POINTS: usize = 120;
+/// Samples between the FPS lines the wrapper logs while monitoring.
+const LOG_EVERY: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PerfEvent {
+    Fps { fps: u32, skipped: u32 },
+    Buffering,
+}
+
+impl PerfEvent {
+    /// Recognizes the `--print-fps` output, e.g. `INFO: 60 fps` or
+    /// `INFO: 58 fps (+2 frames skipped)`, and buffering trouble such as
+    /// `Buffering threshold exceeded, skipping 480 samples` or
+    /// `[Audio] Buffer underflow, inserted 512 silent samples`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if let Some(i) = words.iter().position(|w| *w == "fps") {
+            if let Some(fps) = i.checked_sub(1).and_then(|i| words[i].parse().ok()) {
+                let skipped = words
+                    .get(i + 1)
+                    .and_then(|w| w.strip_prefix("(+"))
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_default();
+                return Some(Self::Fps { fps, skipped });
+            }
+        }
+        let line = line.to_lowercase();
+        if line.contains("buffer")
+            && (line.contains("underflow")
+                || line.contains("threshold exceeded")
+                || line.contains("skipping"))
+        {
+            return Some(Self::Buffering);
+        }
+        None
+    }
+}
+
+/// Collects the performance of a running session from its log.
+#[derive(Debug, Clone, Default)]
+pub struct PerfMonitor {
+    samples: Vec<u32>,
+    skipped_frames: u64,
+    buffering_events: u32,
+}
+
+impl PerfMonitor {
+    pub fn record(&mut self, line: &str) -> Option<PerfEvent> {
+        let event = PerfEvent::parse(line)?;
+        match event {
+            PerfEvent::Fps { fps, skipped } => {
+                self.samples.push(fps);
+                self.skipped_frames += u64::from(skipped);
+            }
+            PerfEvent::Buffering => self.buffering_events += 1,
+        }
+        Some(event)
+    }
+
+    /// One FPS value per second, as printed by scrcpy.
+    pub fn samples(&self) -> &[u32] {
+        &self.samples
+    }
+
+    /// The statistics so far, if scrcpy printed anything to monitor.
+    pub fn summary(&self) -> Option<PerfSummary> {
+        if self.samples.is_empty() && self.buffering_events == 0 {
+            return None;
+        }
+        let chunk = self.samples.len().div_ceil(CHART_POINTS).max(1);
+        Some(PerfSummary {
+            min_fps: self.samples.iter().copied().min(),
+            avg_fps: (!self.samples.is_empty())
+                .then(|| self.samples.iter().sum::<u32>() as f32 / self.samples.len() as f32),
+            max_fps: self.samples.iter().copied().max(),
+            skipped_frames: self.skipped_frames,
+            buffering_events: self.buffering_events,
+            chart: self
+                .samples
+                .chunks(chunk)
+                .map(|c| c.iter().sum::<u32>() / c.len() as u32)
+                .collect(),
+        })
+    }
+
+    /// Every few samples, a line with the recent FPS as a sparkline and the
+    /// statistics so far, for the session log.
+    pub fn live_line(&self) -> Option<String> {
+        if self.samples.is_empty() || !self.samples.len().is_multiple_of(LOG_EVERY) {
+            return None;
+        }
+        let summary = self.summary()?;
+        let recent = &self.samples[self.samples.len().saturating_sub(30)..];
+        let max = summary.max_fps.unwrap_or_default().max(1);
+        let sparkline: String = recent
+            .iter()
+            .map(|fps| ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'][(fps * 7 / max) as usize])
+            .collect();
+        Some(format!("fps {sparkline} {summary}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scrcpy_lines() {
+        assert_eq!(
+            PerfEvent::parse("INFO: 60 fps"),
+            Some(PerfEvent::Fps {
+                fps: 60,
+                skipped: 0
+            })
+        );
+        assert_eq!(
+            PerfEvent::parse("INFO: 57 fps (+3 frames skipped)"),
+            Some(PerfEvent::Fps {
+                fps: 57,
+                skipped: 3
+            })
+        );
+        assert_eq!(
+            PerfEvent::parse("VERBOSE: Buffering threshold exceeded, skipping 480 samples"),
+            Some(PerfEvent::Buffering)
+        );
+        assert_eq!(
+            PerfEvent::parse("DEBUG: [Audio] Buffer underflow, inserted 512 silent samples"),
+            Some(PerfEvent::Buffering)
+        );
+        assert_eq!(PerfEvent::parse("INFO: Renderer: opengl"), None);
+        assert_eq!(PerfEvent::parse("scrcpy --max-fps=60 fps"), None);
+    }
+
+    #[test]
+    fn summarizes_samples() {
+        let mut monitor = PerfMonitor::default();
+        for fps in [30, 60, 60, 58] {
+            monitor.record(&format!("INFO: {fps} fps (+1 frames skipped)"));
+        }
+        monitor.record("WARN: Buffering threshold exceeded, skipping 10 samples");
+        let summary = monitor.summary().unwrap();
+        assert_eq!((summary.min_fps, summary.max_fps), (Some(30), Some(60)));
+        assert_eq!(summary.avg_fps, Some(52.0));
+        assert_eq!(summary.skipped_frames, 4);
+        assert_eq!(summary.buffering_events, 1);
+        assert_eq!(summary.chart, [30, 60, 60, 58]);
+        assert_eq!(
+            summary.to_string(),
+            "min 30 · avg 52.0 · max 60 fps · 4 skipped · 1 buffering"
+        );
+    }
+
+    #[test]
+    fn buffering_alone_has_no_fps() {
+        let mut monitor = PerfMonitor::default();
+        monitor.record("DEBUG: [Audio] Buffer underflow, inserted 512 silent samples");
+        let summary = monitor.summary().unwrap();
+        assert_eq!(summary.min_fps, None);
+        assert_eq!(summary.avg_fps, None);
+        assert_eq!(summary.max_fps, None);
+        assert!(summary.chart.is_empty());
+        assert_eq!(summary.to_string(), "no fps · 0 skipped · 1 buffering");
+    }
+
+    #[test]
+    fn chart_is_averaged_down() {
+        let mut monitor = PerfMonitor::default();
+        for i in 0..CHART_POINTS * 3 {
+            monitor.record(&format!("INFO: {} fps", i % 3 * 30));
+        }
+        let chart = monitor.summary().unwrap().chart;
+        assert_eq!(chart.len(), CHART_POINTS);
+        assert!(chart.iter().all(|fps| *fps == 30));
+    }
+}
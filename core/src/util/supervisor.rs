@@ -0,0 +1,289 @@
+use crate::util::{log, Adb, Device, Stopper};
+use std::io;
+use std::process::ExitStatus;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A session running at least this long is considered healthy again and
+/// resets the retry counter.
+const STABLE_SESSION: Duration = Duration::from_secs(60);
+/// How often a backoff checks whether the session was stopped.
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Whether `serial` (or any device when empty) is listed as `device`.
+/// Wireless devices are reconnected with `adb connect` first.
+fn device_ready(adb: &Adb, serial: &str) -> bool {
+    if serial.contains(':') {
+        let _ = adb.command().args(["connect", serial]).output();
+    }
+    Device::list(adb)
+        .iter()
+        .any(|d| d.state == "device" && (serial.is_empty() || d.serial == serial))
+}
+
+/// Sleeps for `delay` in short slices, returning `false` as soon as
+/// `stopper` is stopped.
+fn wait_unless_stopped(stopper: &Stopper, delay: Duration) -> bool {
+    let until = Instant::now() + delay;
+    while !stopper.stopped() {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        sleep(left.min(STOP_POLL));
+    }
+    false
+}
+
+/// The clock, the waiting and the device checks of [`supervise`].
+trait Supervision {
+    fn now(&self) -> Instant;
+    /// Waits for `delay`, returning `false` if stopped meanwhile.
+    fn wait(&mut self, delay: Duration) -> bool;
+    fn device_ready(&mut self) -> bool;
+}
+
+struct LiveDevice<'a> {
+    adb: &'a Adb,
+    serial: &'a str,
+    stopper: &'a Stopper,
+}
+
+impl Supervision for LiveDevice<'_> {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wait(&mut self, delay: Duration) -> bool {
+        wait_unless_stopped(self.stopper, delay)
+    }
+
+    fn device_ready(&mut self) -> bool {
+        device_ready(self.adb, self.serial)
+    }
+}
+
+/// Runs `launch` until it exits successfully, relaunching after a failure
+/// once the device is back, with exponential backoff and at most
+/// `max_retries` consecutive restarts. Stopping `stopper` ends a backoff
+/// early with an `Interrupted` error.
+pub fn supervise(
+    launch: impl FnMut() -> io::Result<ExitStatus>,
+    adb: &Adb,
+    serial: &str,
+    max_retries: u32,
+    stopper: &Stopper,
+) -> io::Result<ExitStatus> {
+    let mut device = LiveDevice {
+        adb,
+        serial,
+        stopper,
+    };
+    supervise_with(launch, max_retries, &mut device)
+}
+
+fn supervise_with(
+    mut launch: impl FnMut() -> io::Result<ExitStatus>,
+    max_retries: u32,
+    supervision: &mut impl Supervision,
+) -> io::Result<ExitStatus> {
+    let mut attempt = 0;
+    loop {
+        let started = supervision.now();
+        let status = launch()?;
+        if status.success() {
+            return Ok(status);
+        }
+        if supervision.now().duration_since(started) >= STABLE_SESSION {
+            attempt = 0;
+        }
+        loop {
+            if attempt >= max_retries {
+                log(&format!(
+                    "scrcpy exited with {status}, giving up after {max_retries} restarts"
+                ));
+                return Ok(status);
+            }
+            attempt += 1;
+            let delay = backoff(attempt);
+            log(&format!(
+                "scrcpy exited with {status}, retrying in {}s ({attempt}/{max_retries})",
+                delay.as_secs()
+            ));
+            if !supervision.wait(delay) {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "stopped"));
+            }
+            if supervision.device_ready() {
+                break;
+            }
+            log("device not available yet");
+        }
+        log("device is back, restarting scrcpy");
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::os::unix::process::ExitStatusExt;
+    use std::rc::Rc;
+
+    const FAILED: i32 = 1 << 8;
+
+    /// A clock that only moves when told to, recording every wait.
+    struct Fake {
+        start: Instant,
+        elapsed: Rc<Cell<Duration>>,
+        waits: Vec<Duration>,
+        ready: VecDeque<bool>,
+        stop_at_wait: Option<usize>,
+    }
+
+    impl Fake {
+        fn new() -> Self {
+            Self {
+                start: Instant::now(),
+                elapsed: Rc::default(),
+                waits: Vec::new(),
+                ready: VecDeque::new(),
+                stop_at_wait: None,
+            }
+        }
+
+        fn secs(&self) -> Vec<u64> {
+            self.waits.iter().map(Duration::as_secs).collect()
+        }
+    }
+
+    impl Supervision for Fake {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed.get()
+        }
+
+        fn wait(&mut self, delay: Duration) -> bool {
+            self.waits.push(delay);
+            if self.stop_at_wait == Some(self.waits.len()) {
+                return false;
+            }
+            self.elapsed.set(self.elapsed.get() + delay);
+            true
+        }
+
+        fn device_ready(&mut self) -> bool {
+            self.ready.pop_front().unwrap_or(true)
+        }
+    }
+
+    /// Runs `supervise_with`, each launch lasting and ending as scripted
+    /// (failing once the script runs out), and returns the result and the
+    /// number of launches.
+    fn run(
+        fake: &mut Fake,
+        max_retries: u32,
+        script: &[(u64, i32)],
+    ) -> (io::Result<ExitStatus>, usize) {
+        let elapsed = fake.elapsed.clone();
+        let mut launches = 0;
+        let result = supervise_with(
+            || {
+                let (secs, code) = script.get(launches).copied().unwrap_or((0, FAILED));
+                launches += 1;
+                elapsed.set(elapsed.get() + Duration::from_secs(secs));
+                Ok(ExitStatus::from_raw(code))
+            },
+            max_retries,
+            fake,
+        );
+        (result, launches)
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let delays: Vec<u64> = (1..=8).map(|n| backoff(n).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30, 30]);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn success_ends_supervision() {
+        let mut fake = Fake::new();
+        let (result, launches) = run(&mut fake, 5, &[(0, FAILED), (0, FAILED), (0, 0)]);
+        assert!(result.unwrap().success());
+        assert_eq!(launches, 3);
+        assert_eq!(fake.secs(), [1, 2]);
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let mut fake = Fake::new();
+        let (result, launches) = run(&mut fake, 3, &[]);
+        assert_eq!(result.unwrap().into_raw(), FAILED);
+        assert_eq!(launches, 4);
+        assert_eq!(fake.secs(), [1, 2, 4]);
+
+        let mut fake = Fake::new();
+        let (result, launches) = run(&mut fake, 0, &[]);
+        assert!(!result.unwrap().success());
+        assert_eq!(launches, 1);
+        assert!(fake.waits.is_empty());
+    }
+
+    #[test]
+    fn stable_session_resets_the_retries() {
+        let mut fake = Fake::new();
+        let script = [(0, FAILED), (5, FAILED), (60, FAILED)];
+        let (result, launches) = run(&mut fake, 2, &script);
+        assert!(!result.unwrap().success());
+        assert_eq!(launches, 5);
+        assert_eq!(fake.secs(), [1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn waits_for_the_device_within_the_retries() {
+        let mut fake = Fake::new();
+        fake.ready = VecDeque::from([false, false, true]);
+        let (result, launches) = run(&mut fake, 5, &[(0, FAILED), (0, 0)]);
+        assert!(result.unwrap().success());
+        assert_eq!(launches, 2);
+        assert_eq!(fake.secs(), [1, 2, 4]);
+
+        let mut fake = Fake::new();
+        fake.ready = VecDeque::from([false; 3]);
+        let (result, launches) = run(&mut fake, 2, &[]);
+        assert!(!result.unwrap().success());
+        assert_eq!(launches, 1);
+        assert_eq!(fake.secs(), [1, 2]);
+    }
+
+    #[test]
+    fn stop_interrupts_the_wait() {
+        let mut fake = Fake::new();
+        fake.stop_at_wait = Some(2);
+        let (result, launches) = run(&mut fake, 5, &[]);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert_eq!(launches, 2);
+
+        let stopper = Stopper::default();
+        let started = Instant::now();
+        assert!(wait_unless_stopped(&stopper, Duration::from_millis(20)));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        let waiting = stopper.clone();
+        let started = Instant::now();
+        let handle = std::thread::spawn(move || wait_unless_stopped(&waiting, MAX_BACKOFF));
+        sleep(Duration::from_millis(50));
+        stopper.stop();
+        assert!(!handle.join().unwrap());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::{
+    is_executable, ConfigItem, ConnectMethod, DisplayImePolicy, KeyInject, Keyboard, RenderDriver,
+    VideoSource,
+};
+use crate::t;
+use crate::util::{build_raw_args, strip_unsupported, DeviceInfo, ScrcpyInfo};
+use std::path::Path;
+
+pub fn validate(
+    config: &ConfigItem,
+    info: Option<&ScrcpyInfo>,
+    device: Option<&DeviceInfo>,
+) -> Vec<String> {
+    let mut warnings = strip_unsupported(info, &build_raw_args(config)).1;
+
+    match &config.executable {
+        Some(executable) if !is_executable(Path::new(executable)) => warnings.push(
+            t! {
+                en: "scrcpy not found at {}",
+                zh: "未在 {} 找到 scrcpy",
+                (executable)
+            }
+            .to_string(),
+        ),
+        None => warnings.push(
+            t! {
+                en: "scrcpy executable not set",
+                zh: "未设置 scrcpy 可执行文件"
+            }
+            .to_string(),
+        ),
+        _ => {}
+    }
+
+    if let Some(device) = device {
+        if config.video_source.min_sdk() > device.sdk {
+            warnings.push(
+                t! {
+                    en: "Video source {} is not supported by {} (Android {})",
+                    zh: "{1} (Android {2}) 不支持视频源 {0}",
+                    (config.video_source, device.model, device.release)
+                }
+                .to_string(),
+            );
+        }
+        if config.audio_source.min_sdk() > device.sdk {
+            warnings.push(
+                t! {
+                    en: "Audio source {} is not supported by {} (Android {})",
+                    zh: "{1} (Android {2}) 不支持音频源 {0}",
+                    (config.audio_source, device.model, device.release)
+                }
+                .to_string(),
+            );
+        }
+    }
+
+    if config.connect_method == ConnectMethod::Adb
+        && !config.port.trim().is_empty()
+        && parse_port_range(&config.port).is_none()
+    {
+        warnings.push(
+            t! {
+                en: "Invalid port range, expected e.g. 27183:27199",
+                zh: "端口范围无效，例如 27183:27199"
+            }
+            .to_string(),
+        );
+    }
+
+    if config.virtual_display && config.display_width.is_some() != config.display_height.is_some() {
+        warnings.push(
+            t! {
+                en: "Set both the width and height of the virtual display, or neither",
+                zh: "请同时设置或都不设置虚拟显示器的宽度和高度"
+            }
+            .to_string(),
+        );
+    }
+
+    if config.video_source != VideoSource::No
+        && config.video_playback
+        && !RenderDriver::available().contains(&config.render_driver)
+    {
+        warnings.push(
+            t! {
+                en: "Render driver {} is not available on this platform",
+                zh: "渲染驱动 {} 在此平台不可用",
+                (config.render_driver)
+            }
+            .to_string(),
+        );
+    }
+
+    if !config.options.control {
+        return warnings;
+    }
+
+    if config.keyboard != Keyboard::Sdk {
+        if config.key_inject != KeyInject::Mixed {
+            warnings.push(
+                t! {
+                    en: "Text injection mode only applies to the SDK keyboard",
+                    zh: "文本注入模式仅适用于 SDK 键盘"
+                }
+                .to_string(),
+            );
+        }
+        if config.options.legacy_paste {
+            warnings.push(
+                t! {
+                    en: "Legacy paste only applies to the SDK keyboard",
+                    zh: "传统粘贴仅适用于 SDK 键盘"
+                }
+                .to_string(),
+            );
+        }
+    }
+
+    if config.connect_method == ConnectMethod::Otg && !config.options.clipboard_autosync {
+        warnings.push(
+            t! {
+                en: "Clipboard is never synchronized in OTG mode",
+                zh: "OTG 模式下不会同步剪贴板"
+            }
+            .to_string(),
+        );
+    }
+
+    if config.virtual_display
+        && config.options.display_ime_policy != DisplayImePolicy::Fallback
+        && matches!(config.keyboard, Keyboard::Uhid | Keyboard::Aoa)
+    {
+        warnings.push(
+            t! {
+                en: "IME policy has little effect with a physical keyboard (UHID/AOA)",
+                zh: "使用物理键盘 (UHID/AOA) 时输入法策略几乎无效"
+            }
+            .to_string(),
+        );
+    }
+
+    warnings
+}
+
+/// Parses `port` or `first:last` as accepted by `--port`.
+pub fn parse_port_range(s: &str) -> Option<(u16, u16)> {
+    let s = s.trim();
+    let (first, last) = s.split_once(':').unwrap_or((s, s));
+    let first = first.trim().parse::<u16>().ok()?;
+    let last = last.trim().parse::<u16>().ok()?;
+    if first == 0 || first > last {
+        return None;
+    }
+    Some((first, last))
+}
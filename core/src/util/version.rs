@@ -0,0 +1,292 @@
+use crate::config::find_scrcpy_installs;
+use crate::t;
+use crate::util::args::quote;
+use std::fmt::Display;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches('v');
+        let s = s.split(['-', '+', ' ']).next()?;
+        let mut parts = s.split('.').map(|p| p.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.patch == 0 {
+            write!(f, "{}.{}", self.major, self.minor)
+        } else {
+            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        }
+    }
+}
+
+/// Oldest scrcpy release accepting each option. A key with a value (e.g.
+/// `--audio-source=playback`) only matches that exact value.
+pub const OPTION_MIN_VERSION: &[(&str, Version)] = &[
+    ("--no-audio", Version::new(2, 0, 0)),
+    ("--audio-codec", Version::new(2, 0, 0)),
+    ("--audio-codec-options", Version::new(2, 0, 0)),
+    ("--audio-bit-rate", Version::new(2, 0, 0)),
+    ("--audio-buffer", Version::new(2, 0, 0)),
+    ("--video-codec", Version::new(2, 0, 0)),
+    ("--video-codec-options", Version::new(2, 0, 0)),
+    ("--video-bit-rate", Version::new(2, 0, 0)),
+    ("--no-audio-playback", Version::new(2, 1, 0)),
+    ("--no-video-playback", Version::new(2, 1, 0)),
+    ("--no-video", Version::new(2, 1, 0)),
+    ("--display-orientation", Version::new(2, 1, 0)),
+    ("--video-source", Version::new(2, 2, 0)),
+    ("--camera-facing", Version::new(2, 2, 0)),
+    ("--camera-fps", Version::new(2, 2, 0)),
+    ("--time-limit", Version::new(2, 2, 0)),
+    ("--keyboard", Version::new(2, 4, 0)),
+    ("--mouse", Version::new(2, 4, 0)),
+    ("--no-mouse-hover", Version::new(2, 5, 0)),
+    ("--mouse-bind", Version::new(2, 5, 0)),
+    ("--audio-source=playback", Version::new(2, 6, 0)),
+    ("--audio-dup", Version::new(2, 6, 0)),
+    ("--no-window", Version::new(2, 6, 0)),
+    ("--gamepad", Version::new(2, 7, 0)),
+    ("--new-display", Version::new(3, 0, 0)),
+    ("--capture-orientation", Version::new(3, 0, 0)),
+    ("--angle", Version::new(3, 0, 0)),
+    ("--start-app", Version::new(3, 0, 0)),
+    ("--video-buffer", Version::new(3, 0, 0)),
+    ("--no-vd-destroy-content", Version::new(3, 1, 0)),
+    ("--display-ime-policy", Version::new(3, 2, 0)),
+    ("--camera-torch", Version::new(3, 2, 0)),
+];
+
+#[derive(Debug, Clone)]
+pub struct ScrcpyInfo {
+    pub version: Version,
+    pub libraries: Vec<(String, String)>,
+}
+
+impl ScrcpyInfo {
+    pub fn detect(executable: &str) -> Option<Self> {
+        if executable.trim().is_empty() {
+            return None;
+        }
+        let output = Command::new(executable)
+            .arg("--version")
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parses the output of `scrcpy --version`:
+    ///
+    /// ```text
+    /// scrcpy 3.1 <https://github.com/Genymobile/scrcpy>
+    ///
+    /// Dependencies (compiled / linked):
+    ///  - SDL: 2.30.9 / 2.30.9
+    ///  - libavcodec: 61.19.100 / 61.19.100
+    /// ```
+    pub fn parse(output: &str) -> Option<Self> {
+        let mut lines = output.lines();
+        let version = lines
+            .find_map(|l| l.trim().strip_prefix("scrcpy "))
+            .and_then(Version::parse)?;
+        let libraries = lines
+            .filter_map(|l| l.trim().strip_prefix("- "))
+            .filter_map(|l| {
+                let (name, versions) = l.split_once(':').or_else(|| l.split_once(' '))?;
+                // prefer the linked version over the compiled one
+                let version = versions.rsplit('/').next()?.trim();
+                Some((name.trim().to_string(), version.to_string()))
+            })
+            .collect();
+        Some(Self { version, libraries })
+    }
+}
+
+/// A scrcpy found on this machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrcpyInstall {
+    pub path: String,
+    pub version: Option<Version>,
+}
+
+impl Display for ScrcpyInstall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "scrcpy {} · {}", version, self.path),
+            None => write!(f, "scrcpy ? · {}", self.path),
+        }
+    }
+}
+
+impl ScrcpyInstall {
+    pub fn discover() -> Vec<Self> {
+        find_scrcpy_installs()
+            .into_iter()
+            .map(|path| Self {
+                version: ScrcpyInfo::detect(&path).map(|info| info.version),
+                path,
+            })
+            .collect()
+    }
+}
+
+fn min_version(arg: &str) -> Option<Version> {
+    OPTION_MIN_VERSION
+        .iter()
+        .filter(|(key, _)| arg == *key || arg.starts_with(&format!("{key}=")))
+        .map(|(_, version)| *version)
+        .max()
+}
+
+/// Whether the detected scrcpy accepts `option`; assumed true while unknown.
+pub fn option_supported(info: Option<&ScrcpyInfo>, option: &str) -> bool {
+    match (info, min_version(option)) {
+        (Some(info), Some(min)) => info.version >= min,
+        _ => true,
+    }
+}
+
+/// Removes the options the detected scrcpy does not know about, returning
+/// the remaining command line and a warning for each removed option.
+pub fn strip_unsupported(info: Option<&ScrcpyInfo>, args: &str) -> (String, Vec<String>) {
+    let Ok(tokens) = shell_words::split(args) else {
+        return (args.to_string(), vec![]);
+    };
+    let Some(version) = info.map(|i| i.version) else {
+        return (args.to_string(), vec![]);
+    };
+    let mut warnings = Vec::new();
+    let kept: Vec<String> = tokens
+        .into_iter()
+        .filter(|token| match min_version(token) {
+            Some(min) if min > version => {
+                warnings.push(
+                    t! {
+                        en: "{} requires scrcpy {}, found {}",
+                        zh: "{} 需要 scrcpy {}，当前为 {}",
+                        (token.split('=').next().unwrap_or(token), min, version)
+                    }
+                    .to_string(),
+                );
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    if warnings.is_empty() {
+        (args.to_string(), warnings)
+    } else {
+        let kept: Vec<_> = kept.iter().map(|token| quote(token)).collect();
+        (kept.join(" "), warnings)
+    }
+}
+
+/// A short note for widgets whose option the detected scrcpy lacks.
+pub fn requirement_hint(info: Option<&ScrcpyInfo>, option: &str) -> Option<String> {
+    if option_supported(info, option) {
+        return None;
+    }
+    min_version(option).map(|min| {
+        t! {
+            en: "requires scrcpy {}",
+            zh: "需要 scrcpy {}",
+            (min)
+        }
+        .to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(Version::parse("3.1"), Some(Version::new(3, 1, 0)));
+        assert_eq!(Version::parse(" v2.7.1 "), Some(Version::new(2, 7, 1)));
+        assert_eq!(Version::parse("3"), Some(Version::new(3, 0, 0)));
+        assert_eq!(Version::parse("3.2-rc1"), Some(Version::new(3, 2, 0)));
+        assert_eq!(
+            Version::parse("1.25 <https://github.com/Genymobile/scrcpy>"),
+            Some(Version::new(1, 25, 0))
+        );
+        for invalid in ["", "v", "three", "3.x", "3..1"] {
+            assert_eq!(Version::parse(invalid), None, "{invalid}");
+        }
+        assert!(Version::new(2, 10, 0) > Version::new(2, 9, 5));
+    }
+
+    #[test]
+    fn parses_version_output() {
+        let info = ScrcpyInfo::parse(
+            "scrcpy 3.1 <https://github.com/Genymobile/scrcpy>\n\
+             \n\
+             Dependencies (compiled / linked):\n \
+             - SDL: 2.30.9 / 2.30.10\n \
+             - libavcodec: 61.19.100 / 61.19.100\n \
+             - libavformat: 61.7.100 / 61.7.100\n \
+             - libusb: - / 1.0.27\n",
+        )
+        .unwrap();
+        assert_eq!(info.version, Version::new(3, 1, 0));
+        assert_eq!(
+            info.libraries,
+            [
+                ("SDL", "2.30.10"),
+                ("libavcodec", "61.19.100"),
+                ("libavformat", "61.7.100"),
+                ("libusb", "1.0.27"),
+            ]
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+        );
+
+        let old =
+            ScrcpyInfo::parse("scrcpy 1.24 <https://github.com/Genymobile/scrcpy>\n").unwrap();
+        assert_eq!(old.version, Version::new(1, 24, 0));
+        assert!(old.libraries.is_empty());
+
+        assert!(ScrcpyInfo::parse("").is_none());
+        assert!(ScrcpyInfo::parse("bash: scrcpy: command not found\n").is_none());
+    }
+
+    #[test]
+    fn strips_options_newer_than_scrcpy() {
+        let args = "--serial=R58M --gamepad=uhid --new-display=1920x1080 --max-fps=30";
+        assert_eq!(strip_unsupported(None, args), (args.to_string(), vec![]));
+
+        let info = ScrcpyInfo {
+            version: Version::new(2, 7, 0),
+            libraries: vec![],
+        };
+        let (kept, warnings) = strip_unsupported(Some(&info), args);
+        assert_eq!(kept, "--serial=R58M --gamepad=uhid --max-fps=30");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("--new-display"), "{warnings:?}");
+        assert!(option_supported(Some(&info), "--gamepad"));
+        assert!(!option_supported(Some(&info), "--angle"));
+        assert!(option_supported(None, "--angle"));
+    }
+}
@@ -1,2 +0,0 @@
-mod conf;
-pub use conf::*;
\ No newline at end of file
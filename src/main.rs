@@ -1,27 +1,15 @@
-use crate::config::{Config, ConfigRaw};
+use crate::config::{Config, ConfigItem, ConfigRaw};
 use iced::window::Settings;
 use iced::{Font, Size};
-use once_cell::sync::Lazy;
+use scrcpy_wrapper_core::{config, i18n, t};
 use std::error::Error;
-use std::process::Stdio;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use sys_locale::get_locale;
 
-mod config;
-pub mod i18n;
 mod ui;
 mod util;
 
-pub static CONFIG: Lazy<RwLock<Box<Config>>> = Lazy::new(|| {
-    RwLock::new(Box::new(
-        ConfigRaw::load()
-            .unwrap_or_default()
-            .to_config(true)
-            .unwrap(),
-    ))
-});
-
-pub static ARGS: RwLock<Option<String>> = RwLock::new(None);
 fn main() -> Result<(), Box<dyn Error>> {
     if let Some(locale) = get_locale() {
         if locale.starts_with("zh") {
@@ -29,8 +17,126 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     }
 
-    drop(CONFIG.read().unwrap());
+    // `run [PROFILE]` launches without the window, e.g. from exported launchers;
+    // `--serve` serves the control API, alongside the session or on its own
+    let mut cli: Vec<String> = std::env::args().skip(1).collect();
+    let headless = cli.first().is_some_and(|arg| arg == "run");
+    let mut profile = None;
+    if headless {
+        cli.remove(0);
+        if cli.first().is_some_and(|arg| !arg.starts_with('-')) {
+            profile = Some(cli.remove(0));
+        }
+    }
+    let serve = cli.iter().any(|arg| arg == "--serve");
 
+    let mut config = ConfigRaw::load().unwrap_or_default().to_config();
+    config.default.apply_language();
+    config.detect_installs();
+    if let Some(name) = &profile {
+        let Some(item) = config.saved.get(name) else {
+            return Err(format!("unknown profile {name}").into());
+        };
+        config.default = item.clone();
+    }
+    apply_cli_args(&mut config.default, cli.clone());
+    if headless && profile.is_none() {
+        if let Some(name) = rule_profile(&config) {
+            config.default = config.saved[&name].clone();
+            apply_cli_args(&mut config.default, cli);
+            profile = Some(name);
+        }
+    }
+    if serve && !config.control_api {
+        return Err("--serve needs control_api = true in the config".into());
+    }
+
+    // sessions launched over the API are stopped when this drops, on exit
+    let mut _control_api = None;
+    let launch = if headless {
+        if serve {
+            _control_api = Some(ControlApi::start(&config));
+        }
+        let info = config
+            .default
+            .executable
+            .as_deref()
+            .and_then(util::ScrcpyInfo::detect);
+        ui::Launch {
+            args: util::build_args(&config.default, info.as_ref()),
+            config,
+            profile,
+        }
+    } else {
+        if serve {
+            // only the control API, until it fails
+            let server = Arc::new(util::ControlServer::load());
+            let result = server.clone().serve(&util::control_socket(&config));
+            server.shutdown();
+            return Ok(result?);
+        }
+        if config.control_api {
+            _control_api = Some(ControlApi::start(&config));
+        }
+        let Some(launch) = run_window(config) else {
+            return Ok(());
+        };
+        let _ = launch.config.to_raw().dump();
+        launch
+    };
+
+    let config = launch.config.default.clone();
+    let exe = config.executable.clone().unwrap_or_default();
+    let env = util::child_env(launch.config.adb(), &config);
+    let mut launcher = util::Launcher::new(exe, launch.args, config, launch.profile, env);
+
+    // a failure, such as an aborting pre-launch hook, exits non-zero
+    match launcher.run_session(&util::Adb::new(&launch.config)) {
+        util::SessionEnd::Failed(e) => Err(e.into()),
+        util::SessionEnd::Exited(_) | util::SessionEnd::Stopped => Ok(()),
+    }
+}
+
+/// The saved profile a device rule picks for the selected device, as the
+/// window does once it has probed the device.
+fn rule_profile(config: &Config) -> Option<String> {
+    let serial = config.default.serial.trim();
+    if serial.is_empty() {
+        return None;
+    }
+    let info = util::DeviceInfo::probe(&util::Adb::new(config), serial)?;
+    let (name, _) = config.profile_for_device(&info.serial, &info.model, info.sdk)?;
+    util::log(&format!("device rule picked profile {name}"));
+    Some(name.to_string())
+}
+
+/// The control API serving in the background.
+struct ControlApi(Arc<util::ControlServer>);
+
+impl ControlApi {
+    fn start(config: &Config) -> Self {
+        let server = Arc::new(util::ControlServer::load());
+        let path = util::control_socket(config);
+        thread::spawn({
+            let server = server.clone();
+            move || {
+                if let Err(e) = server.serve(&path) {
+                    util::log(&format!("control API stopped: {e}"));
+                }
+            }
+        });
+        Self(server)
+    }
+}
+
+impl Drop for ControlApi {
+    fn drop(&mut self) {
+        self.0.shutdown();
+    }
+}
+
+/// Shows the window until it closes, returning the session to start if any.
+fn run_window(config: Config) -> Option<ui::Launch> {
     let font = Font::with_name(if cfg!(target_os = "windows") {
         "Microsoft YaHei"
     } else if cfg!(target_os = "macos") {
@@ -39,7 +145,12 @@ fn main() -> Result<(), Box<dyn Error>> {
         "Noto Sans CJK SC"
     });
 
-    iced::application(ui::WinMain::default, ui::WinMain::update, ui::WinMain::view)
+    let launch = Arc::new(Mutex::new(None));
+    let boot = {
+        let launch = launch.clone();
+        move || ui::WinMain::new(config.clone(), launch.clone())
+    };
+    iced::application(boot, ui::WinMain::update, ui::WinMain::view)
         .window(Settings {
             size: Size {
                 width: 800.0,
@@ -59,32 +170,25 @@ fn main() -> Result<(), Box<dyn Error>> {
         .run()
         .unwrap();
 
-    let exe = CONFIG
-        .read()
-        .unwrap()
-        .default
-        .executable
-        .clone()
-        .unwrap_or_default();
-    let command = ARGS.read().unwrap().clone();
-
-    if let Some(args) = command {
-        let _ = CONFIG.read().unwrap().to_raw().dump();
-
-        let mut p = std::process::Command::new(exe);
+    let launch = launch.lock().unwrap().take();
+    launch
+}
 
-        if !args.trim().is_empty() {
-            let args = shell_words::split(&args).unwrap();
-            p.args(args);
+/// Applies scrcpy-style options from the command line: `-s SERIAL` /
+/// `--serial=SERIAL` and any option of the registry, e.g. `--no-mipmaps`.
+fn apply_cli_args(config: &mut ConfigItem, args: Vec<String>) {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if let Some(serial) = arg.strip_prefix("--serial=") {
+            config.serial = serial.to_string();
+        } else if arg == "-s" || arg == "--serial" {
+            if let Some(serial) = args.next() {
+                config.serial = serial;
+            }
+        } else if arg == "--serve" {
+            // handled in main
+        } else if !config.options.apply_arg(&arg) {
+            util::log(&format!("ignoring unknown option {arg}"));
         }
-
-        p.stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn()
-            .unwrap()
-            .wait()
-            .unwrap();
     }
-
-    Ok(())
 }
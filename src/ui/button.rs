@@ -1,12 +1,7 @@
 use crate::{d_button, d_pick_list};
+pub use scrcpy_wrapper_core::config::ButtonState;
 use std::fmt::Display;
 
-pub trait ButtonState {
-    fn states() -> Vec<Self>
-    where
-        Self: Sized;
-}
-
 pub struct StateButton<'a, T: ButtonState + Sized + Clone + PartialEq + Display, Message> {
     value: T,
     on_press: Box<dyn Fn(T) -> Message + 'a>,
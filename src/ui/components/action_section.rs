@@ -1,8 +1,27 @@
 use crate::ui::{style_default, Message};
+use crate::util::validate;
 use crate::{d_column, d_row, d_text_input, define_component, t};
 use iced::widget::{button, horizontal_space, text};
 
-define_component!(action_section, |_, win_main| {
+define_component!(action_section, |config, win_main| {
+    let monitoring = win_main.monitor.as_ref().is_some_and(|m| m.end().is_none());
+    let mut warnings = validate(
+        &config.default,
+        win_main.scrcpy_info.as_ref(),
+        win_main.device_info.as_ref(),
+    );
+    let invalid = win_main.drafts.has_errors(&config.default);
+    if invalid {
+        warnings.insert(
+            0,
+            t! {
+                en: "Fix the invalid fields before running",
+                zh: "运行前请修正无效的字段"
+            }
+            .to_string(),
+        );
+    }
+
     d_column![
         d_text_input!(
             &t! {
@@ -13,8 +32,10 @@ define_component!(action_section, |_, win_main| {
         )
         .on_input(Message::ArgsChanged),
         d_row![
+            text(warnings.join("; ")).color([0.9, 0.6, 0.0]),
             horizontal_space(),
-            button(text(t! {en: "Run", zh: "运行"}.to_string())).on_press(Message::Run)
+            button(text(t! {en: "Run", zh: "运行"}.to_string()))
+                .on_press_maybe((!monitoring && !invalid).then_some(Message::Run))
         ]
     ]
     .padding(style_default::Padding::page())
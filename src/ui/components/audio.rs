@@ -1,9 +1,9 @@
-use crate::config::AudioSource;
-use crate::ui::{Message, StateButton};
-use crate::{d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use crate::config::{AudioCodec, AudioSource, OptionKey};
+use crate::ui::{option_field, ButtonState, Message, StateButton};
+use crate::{d_column, d_pick_list, d_row, d_sub_title, d_text_input, define_component, t};
 use iced::widget::{checkbox, text};
 
-define_component!(audio, |config, _| {
+define_component!(audio, |config, win_main| {
     let sub_title = d_sub_title!(t! {
         en: "Audio",
         zh: "音频"
@@ -20,7 +20,11 @@ define_component!(audio, |config, _| {
             }
             .to_string()
         ),
-        StateButton::pick_list(config.default.audio_source, Message::AudioSourceChanged)
+        d_pick_list!(
+            AudioSource::available(win_main.device_info.as_ref().map(|d| d.sdk)),
+            Some(config.default.audio_source),
+            Message::AudioSourceChanged
+        )
     ];
 
     if config.default.audio_source == AudioSource::No {
@@ -28,17 +32,7 @@ define_component!(audio, |config, _| {
     }
 
     if config.default.audio_source == AudioSource::Playback {
-        source = source.push(
-            checkbox(
-                t! {
-                    en: "duplicate audio",
-                    zh: "复制音频"
-                }
-                .to_string(),
-                config.default.audio_dup,
-            )
-            .on_toggle(Message::AudioDupChanged),
-        );
+        source = source.push(option_field(OptionKey::AudioDup, win_main));
     }
 
     source = source.push(
@@ -74,5 +68,38 @@ define_component!(audio, |config, _| {
             .on_input(Message::AudioCodecOptionsChanged)
     ];
 
-    column.push(source).push(codec).into()
+    let mut codec_fallback = d_row![text(
+        t! {
+            en: "Fall back to: ",
+            zh: "失败时改用："
+        }
+        .to_string()
+    )];
+    for codec in AudioCodec::states() {
+        if codec != config.default.audio_codec {
+            codec_fallback = codec_fallback.push(
+                checkbox(
+                    codec.to_string(),
+                    config.default.audio_codec_fallback.contains(&codec),
+                )
+                .on_toggle(move |enabled| Message::AudioCodecFallbackChanged(codec, enabled)),
+            );
+        }
+    }
+    if !config.default.audio_codec_fallback.is_empty() {
+        let chain: Vec<String> = std::iter::once(config.default.audio_codec)
+            .chain(
+                config
+                    .default
+                    .audio_codec_fallback
+                    .iter()
+                    .copied()
+                    .filter(|c| *c != config.default.audio_codec),
+            )
+            .map(|c| c.to_string())
+            .collect();
+        codec_fallback = codec_fallback.push(text(chain.join(" → ")).color([0.5, 0.5, 0.5]));
+    }
+
+    column.push(source).push(codec).push(codec_fallback).into()
 });
@@ -0,0 +1,61 @@
+use crate::config::{ConnectMethod, Keyboard, OptionKey};
+use crate::ui::{option_field, Message, StateButton};
+use crate::{d_column, d_row, d_sub_title, define_component, t};
+use iced::widget::text;
+
+define_component!(clipboard, |config, win_main| {
+    let sub_title = d_sub_title!(t! {
+        en: "Clipboard & text input",
+        zh: "剪贴板与文本输入"
+    }
+    .to_string(),);
+
+    let mut column = d_column![sub_title];
+
+    if !config.default.options.control {
+        return column
+            .push(
+                text(
+                    t! {
+                        en: "Control disabled",
+                        zh: "已禁用控制"
+                    }
+                    .to_string(),
+                )
+                .color([0.5, 0.5, 0.5]),
+            )
+            .into();
+    }
+
+    if config.default.keyboard == Keyboard::Sdk {
+        let key_inject = d_row![
+            text(
+                t! {
+                    en: "Text injection: ",
+                    zh: "文本注入："
+                }
+                .to_string()
+            ),
+            StateButton::pick_list(config.default.key_inject, Message::KeyInjectChanged)
+        ];
+        let legacy_paste = option_field(OptionKey::LegacyPaste, win_main);
+        column = column.push(key_inject).push(legacy_paste);
+    } else {
+        column = column.push(
+            text(
+                t! {
+                    en: "Text injection is only available with the SDK keyboard",
+                    zh: "仅 SDK 键盘支持文本注入设置"
+                }
+                .to_string(),
+            )
+            .color([0.5, 0.5, 0.5]),
+        );
+    }
+
+    if config.default.connect_method != ConnectMethod::Otg {
+        column = column.push(option_field(OptionKey::ClipboardAutosync, win_main));
+    }
+
+    column.into()
+});
@@ -1,9 +1,10 @@
-use crate::ui::Message;
+use crate::config::PRESETS;
+use crate::ui::{Message, StateButton};
 use crate::util::select_config_valid;
 use crate::{
     d_button, d_column, d_pick_list, d_row, d_sub_title, d_text_input, define_component, t,
 };
-use iced::widget::text;
+use iced::widget::{checkbox, text};
 
 define_component!(config, |config, win_main| {
     let keys: Vec<String> = config.saved.keys().cloned().collect();
@@ -21,7 +22,7 @@ define_component!(config, |config, win_main| {
                 en: "Saved Config: ",
                 zh: "保存的配置："
             }
-            .to_string()                    
+            .to_string()
         ),
         d_pick_list!(keys, chosed_config, Message::ConfigSelectChanged)
     ];
@@ -57,6 +58,73 @@ define_component!(config, |config, win_main| {
             None
         })
     ];
+    let mut presets = d_column![d_row![
+        text(
+            t! {
+                en: "Preset: ",
+                zh: "预设："
+            }
+            .to_string()
+        ),
+        d_pick_list!(
+            PRESETS.iter().collect::<Vec<_>>(),
+            win_main.config_status.preset,
+            Message::PresetSelected
+        ),
+        d_button!(t! {
+            en: "Apply",
+            zh: "应用"
+        }
+        .to_string())
+        .on_press_maybe(win_main.config_status.preset.map(|_| Message::PresetApply)),
+        d_button!(t! {
+            en: "Save as config",
+            zh: "另存为配置"
+        }
+        .to_string())
+        .on_press_maybe(win_main.config_status.preset.map(|_| Message::PresetClone)),
+    ]];
+    if let Some(preset) = win_main.config_status.preset {
+        presets = presets.push(text(preset.description()).color([0.5, 0.5, 0.5]));
+    }
+    if let Some(saved) = &win_main.config_status.preset_saved {
+        presets = presets.push(text(saved.clone()).color([0.5, 0.5, 0.5]));
+    }
+    let mut export = d_column![d_row![
+        text(
+            t! {
+                en: "Launcher: ",
+                zh: "启动器："
+            }
+            .to_string()
+        ),
+        StateButton::pick_list(
+            win_main.config_status.export_format,
+            Message::ExportFormatChanged
+        ),
+        checkbox(
+            t! {
+                en: "through scrcpy-wrapper (follows later edits)",
+                zh: "通过 scrcpy-wrapper 启动（跟随之后的修改）"
+            }
+            .to_string(),
+            win_main.config_status.export_via_wrapper,
+        )
+        .on_toggle(Message::ExportViaWrapperChanged),
+        d_button!(t! {
+            en: "Export",
+            zh: "导出"
+        }
+        .to_string())
+        .on_press_maybe(if valid {
+            Some(Message::ExportLauncher)
+        } else {
+            None
+        }),
+    ]];
+    if let Some(exported) = &win_main.config_status.exported {
+        export = export.push(text(exported.clone()).color([0.5, 0.5, 0.5]));
+    }
     let save_actions = d_row![
         text(
             t! {
@@ -73,6 +141,42 @@ define_component!(config, |config, win_main| {
         .to_string())
         .on_press(Message::ConfigSave),
     ];
+
+    let mut device_rules = d_column![d_row![
+        text(
+            t! {
+                en: "Device rules",
+                zh: "设备规则"
+            }
+            .to_string()
+        ),
+        d_button!(t! {
+            en: "Load chosen config for this device",
+            zh: "此设备自动加载所选配置"
+        }
+        .to_string())
+        .on_press_maybe(if valid && !config.default.serial.is_empty() {
+            Some(Message::DeviceRuleAdd)
+        } else {
+            None
+        }),
+    ]];
+    for (i, rule) in config.rules.iter().enumerate() {
+        let mut label = text(rule.to_string());
+        if !config.saved.contains_key(&rule.profile) {
+            label = label.color([0.9, 0.6, 0.0]);
+        }
+        device_rules = device_rules.push(d_row![
+            label,
+            d_button!(t! {
+                en: "Delete",
+                zh: "删除"
+            }
+            .to_string())
+            .on_press(Message::DeviceRuleDelete(i)),
+        ]);
+    }
+
     d_column![
         d_sub_title!(t! {
             en: "Config",
@@ -81,7 +185,10 @@ define_component!(config, |config, win_main| {
         .to_string()),
         saved_config,
         saved_config_actions,
-        save_actions
+        export,
+        save_actions,
+        presets,
+        device_rules
     ]
     .into()
 });
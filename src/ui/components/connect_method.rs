@@ -1,16 +1,123 @@
-use crate::ui::{Message, StateButton};
-use crate::{d_row, define_component, t};
-use iced::widget::{horizontal_space, text};
+use crate::config::{ConnectMethod, OptionKey};
+use crate::ui::{option_field, typed_input, Message, StateButton};
+use crate::util::{parse_port_range, Field};
+use crate::{d_button, d_column, d_pick_list, d_row, d_text_input, define_component, t};
+use iced::widget::{checkbox, horizontal_space, text};
 
-define_component!(connect_method, |config, _| {
-    d_row![
+define_component!(connect_method, |config, win_main| {
+    let mut method = d_row![
         text(&t! {r
             zh: "连接方式：",
             en: "Connecting method: "
         }),
         StateButton::button(config.default.connect_method, Message::ConnectMethodChanged),
-        horizontal_space(),
-        StateButton::button(config.default.language, Message::LanguageChanged)
-    ]
-    .into()
+    ];
+
+    if config.default.connect_method == ConnectMethod::Adb {
+        method = method.push(
+            d_button!(if win_main.advanced_connection {
+                t! {en: "Advanced ▾", zh: "高级 ▾"}.to_string()
+            } else {
+                t! {en: "Advanced ▸", zh: "高级 ▸"}.to_string()
+            })
+            .on_press(Message::AdvancedConnectionToggled),
+        );
+    }
+
+    let method = method.push(horizontal_space()).push(StateButton::button(
+        config.default.language,
+        Message::LanguageChanged,
+    ));
+
+    let selected = win_main
+        .devices
+        .iter()
+        .find(|d| d.serial == config.default.serial)
+        .cloned();
+    let device = d_row![
+        text(
+            t! {
+                en: "Device: ",
+                zh: "设备："
+            }
+            .to_string()
+        ),
+        d_pick_list!(win_main.devices.clone(), selected, Message::DeviceSelected)
+            .placeholder(config.default.serial.clone()),
+        d_button!(t! {en: "Refresh", zh: "刷新"}.to_string()).on_press(Message::RefreshDevices),
+    ];
+    let column = d_column![method, device];
+
+    if config.default.connect_method != ConnectMethod::Adb || !win_main.advanced_connection {
+        return column.into();
+    }
+
+    let mut port = d_row![
+        text(
+            t! {
+                en: "Port: ",
+                zh: "端口："
+            }
+            .to_string()
+        ),
+        d_text_input!("27183:27199", &config.default.port)
+            .width(120)
+            .on_input(Message::PortChanged),
+    ];
+    if !config.default.port.is_empty() && parse_port_range(&config.default.port).is_none() {
+        port = port.push(
+            text(
+                t! {
+                    en: "invalid port range",
+                    zh: "端口范围无效"
+                }
+                .to_string(),
+            )
+            .color([0.9, 0.6, 0.0]),
+        );
+    }
+
+    let auto_reconnect = d_row![
+        checkbox(
+            t! {en: "Reconnect when scrcpy exits with an error", zh: "scrcpy 异常退出时自动重连"}
+                .to_string(),
+            config.default.auto_reconnect,
+        )
+        .on_toggle(Message::AutoReconnectChanged),
+        text(
+            t! {
+                en: "max retries: ",
+                zh: "最大重试次数："
+            }
+            .to_string()
+        ),
+        typed_input(
+            &win_main.drafts,
+            Field::ReconnectRetries,
+            "5",
+            Some(config.default.reconnect_retries),
+            60,
+            config
+                .default
+                .auto_reconnect
+                .then_some(Message::ReconnectRetriesChanged),
+        ),
+    ];
+
+    column
+        .push(port)
+        .push(d_row![
+            option_field(OptionKey::TunnelHost, win_main),
+            option_field(OptionKey::TunnelPort, win_main),
+        ])
+        .push(d_row![
+            option_field(OptionKey::ForceAdbForward, win_main),
+            option_field(OptionKey::KillAdbOnClose, win_main),
+        ])
+        .push(d_row![
+            option_field(OptionKey::Cleanup, win_main),
+            option_field(OptionKey::DownsizeOnError, win_main),
+        ])
+        .push(auto_reconnect)
+        .into()
 });
@@ -1,15 +1,36 @@
-use crate::ui::{Message, StateButton};
+use crate::config::{Keyboard, Mouse, MouseBind, OptionKey, ShortcutMod};
+use crate::ui::{option_field, ButtonState, Message, StateButton};
 use crate::{d_column, d_row, d_sub_title, define_component, t};
-use iced::widget::text;
+use iced::widget::{checkbox, text};
 
-define_component!(control, |config, _| {
+define_component!(control, |config, win_main| {
     let sub_title = d_sub_title!(t! {
         en: "Control",
         zh: "控制"
     }
     .to_string(),);
 
-    let keyboard = d_row![
+    let mut column = d_column![sub_title, option_field(OptionKey::Control, win_main)];
+
+    let mut shortcut_mod = d_row![text(
+        t! {
+            en: "Shortcut modifiers: ",
+            zh: "快捷键修饰键："
+        }
+        .to_string()
+    )];
+    for m in ShortcutMod::states() {
+        shortcut_mod = shortcut_mod.push(
+            checkbox(m.to_string(), config.default.shortcut_mod.contains(&m))
+                .on_toggle(move |enabled| Message::ShortcutModChanged(m, enabled)),
+        );
+    }
+
+    if !config.default.options.control {
+        return column.push(shortcut_mod).into();
+    }
+
+    let mut keyboard = d_row![
         text(
             t! {
                 en: "Keyboard input modes: ",
@@ -19,7 +40,11 @@ define_component!(control, |config, _| {
         ),
         StateButton::pick_list(config.default.keyboard, Message::KeyboardChanged)
     ];
-    let mouse = d_row![
+    if config.default.keyboard == Keyboard::Sdk {
+        keyboard = keyboard.push(option_field(OptionKey::KeyRepeat, win_main));
+    }
+
+    let mut mouse = d_row![
         text(
             t! {
                 en: "Mouse input modes: ",
@@ -29,16 +54,69 @@ define_component!(control, |config, _| {
         ),
         StateButton::pick_list(config.default.mouse, Message::MouseChanged)
     ];
-    let gamepad = d_row![
-        text(
-            t! {
-                en: "Gamepad input modes: ",
-                zh: "游戏手柄输入模式："
-            }
-            .to_string()
-        ),
-        StateButton::pick_list(config.default.gamepad, Message::GamepadChanged)
-    ];
+    if config.default.mouse == Mouse::Sdk {
+        mouse = mouse.push(option_field(OptionKey::MouseHover, win_main));
+    }
+    if config.default.mouse != Mouse::Disabled {
+        mouse = mouse.push(
+            checkbox(
+                t! {
+                    en: "Custom bindings",
+                    zh: "自定义按键"
+                }
+                .to_string(),
+                config.default.mouse_bind.is_some(),
+            )
+            .on_toggle_maybe(
+                win_main
+                    .supports("--mouse-bind")
+                    .then_some(Message::CustomMouseBindChanged),
+            ),
+        );
+    }
+
+    let gamepad = option_field(OptionKey::Gamepad, win_main);
+
+    column = column.push(keyboard).push(mouse);
+
+    if let Some(bindings) = config
+        .default
+        .mouse_bind
+        .filter(|_| config.default.mouse != Mouse::Disabled)
+    {
+        let binding = |i: usize| {
+            StateButton::pick_list(bindings[i], move |b: MouseBind| {
+                Message::MouseBindChanged(i, b)
+            })
+        };
+        column = column
+            .push(d_row![
+                text(
+                    t! {
+                        en: "Right / middle / 4th / 5th click: ",
+                        zh: "右键 / 中键 / 第 4 键 / 第 5 键："
+                    }
+                    .to_string()
+                ),
+                binding(0),
+                binding(1),
+                binding(2),
+                binding(3)
+            ])
+            .push(d_row![
+                text(
+                    t! {
+                        en: "With Shift: ",
+                        zh: "按住 Shift："
+                    }
+                    .to_string()
+                ),
+                binding(4),
+                binding(5),
+                binding(6),
+                binding(7)
+            ]);
+    }
 
-    d_column![sub_title, keyboard, mouse, gamepad].into()
+    column.push(gamepad).push(shortcut_mod).into()
 });
@@ -0,0 +1,96 @@
+use crate::{d_column, d_sub_title, define_component, t};
+use iced::widget::text;
+
+define_component!(device, |_, win_main| {
+    let sub_title = d_sub_title!(t! {
+        en: "Device",
+        zh: "设备"
+    }
+    .to_string(),);
+
+    let column = d_column![sub_title];
+
+    let Some(info) = &win_main.device_info else {
+        return column
+            .push(
+                text(
+                    t! {
+                        en: "No device selected",
+                        zh: "未选择设备"
+                    }
+                    .to_string(),
+                )
+                .color([0.5, 0.5, 0.5]),
+            )
+            .into();
+    };
+
+    let model = text(
+        t! {
+            en: "Model: {} {}",
+            zh: "型号：{} {}",
+            (info.manufacturer, info.model)
+        }
+        .to_string(),
+    );
+
+    let android = text(
+        t! {
+            en: "Android: {} (SDK {}) · {}",
+            zh: "安卓：{} (SDK {}) · {}",
+            (info.release, info.sdk, info.abi)
+        }
+        .to_string(),
+    );
+
+    let mut screen = match info.screen_size {
+        Some((width, height)) => format!("{width}x{height}"),
+        None => "-".to_string(),
+    };
+    if let Some(density) = info.density {
+        screen.push_str(&format!(" @ {density} dpi"));
+    }
+    let screen = text(
+        t! {
+            en: "Screen: {}",
+            zh: "屏幕：{}",
+            (screen)
+        }
+        .to_string(),
+    );
+
+    let battery = match &info.battery {
+        Some(battery) if battery.powered_by.is_empty() => {
+            format!("{}% · {}", battery.level, battery.status)
+        }
+        Some(battery) => format!(
+            "{}% · {} ({})",
+            battery.level,
+            battery.status,
+            battery.powered_by.join(", ")
+        ),
+        None => "-".to_string(),
+    };
+    let battery = text(
+        t! {
+            en: "Battery: {}",
+            zh: "电池：{}",
+            (battery)
+        }
+        .to_string(),
+    );
+
+    let connection = text(if info.is_tcp() {
+        t! {en: "Connection: TCP/IP", zh: "连接：TCP/IP"}.to_string()
+    } else {
+        t! {en: "Connection: USB", zh: "连接：USB"}.to_string()
+    });
+
+    column
+        .push(model)
+        .push(android)
+        .push(screen)
+        .push(battery)
+        .push(connection)
+        .into()
+});
@@ -0,0 +1,52 @@
+use crate::ui::Message;
+use crate::{d_button, d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use iced::widget::text;
+
+define_component!(environment, |config, _| {
+    let sub_title = d_sub_title!(t! {
+        en: "Environment",
+        zh: "环境变量"
+    }
+    .to_string(),);
+
+    let mut column = d_column![
+        sub_title,
+        text(
+            t! {
+                en: "Passed to scrcpy, adb and the hooks, e.g. ANDROID_SERIAL, SCRCPY_SERVER_PATH, ADB_SERVER_SOCKET.",
+                zh: "传递给 scrcpy、adb 和钩子，例如 ANDROID_SERIAL、SCRCPY_SERVER_PATH、ADB_SERVER_SOCKET。"
+            }
+            .to_string()
+        )
+        .color([0.5, 0.5, 0.5])
+    ];
+
+    for (i, (key, value)) in config.default.env.iter().enumerate() {
+        column = column.push(d_row![
+            d_text_input!(
+                &t! {
+                    en: "Name",
+                    zh: "名称"
+                },
+                key
+            )
+            .width(220)
+            .on_input(move |key| Message::EnvKeyChanged(i, key)),
+            text("="),
+            d_text_input!(
+                &t! {
+                    en: "Value",
+                    zh: "值"
+                },
+                value
+            )
+            .width(400)
+            .on_input(move |value| Message::EnvValueChanged(i, value)),
+            d_button!(t! {en: "Delete", zh: "删除"}.to_string()).on_press(Message::EnvRemoved(i)),
+        ]);
+    }
+
+    column
+        .push(d_button!(t! {en: "Add", zh: "添加"}.to_string()).on_press(Message::EnvAdded))
+        .into()
+});
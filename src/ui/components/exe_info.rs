@@ -1,8 +1,10 @@
+use crate::config::is_executable;
 use crate::ui::Message;
-use crate::{d_row, d_text_input, define_component, t};
+use crate::{d_button, d_column, d_pick_list, d_row, d_text_input, define_component, t};
 use iced::widget::text;
+use std::path::Path;
 
-define_component!(exe_info, |config, _| {
+define_component!(exe_info, |config, win_main| {
     let executable_path = {
         let exe_str = match &config.default.executable {
             None => String::from(""),
@@ -16,6 +18,7 @@ define_component!(exe_info, |config, _| {
             &exe_str,
         )
         .on_input(Message::ExecutablePathChanged)
+        .on_submit(Message::DetectScrcpy)
     };
     let executable_path_row = d_row![
         text(&t! {r
@@ -25,5 +28,92 @@ define_component!(exe_info, |config, _| {
         executable_path,
     ];
 
-    executable_path_row.into()
+    let selected = win_main
+        .scrcpy_installs
+        .iter()
+        .find(|i| Some(&i.path) == config.default.executable.as_ref())
+        .cloned();
+    let installs_row = d_row![
+        text(&t! {r
+            en: "Installations: ",
+            zh: "已安装："
+        }),
+        d_pick_list!(
+            win_main.scrcpy_installs.clone(),
+            selected,
+            Message::ScrcpyInstallPicked
+        )
+        .placeholder(t! {en: "Pick an installation", zh: "选择一个安装"}.to_string()),
+        d_button!(t! {en: "Rescan", zh: "重新扫描"}.to_string())
+            .on_press(Message::ScanScrcpyInstalls),
+    ];
+
+    let stale = match &config.default.executable {
+        Some(executable) if !is_executable(Path::new(executable)) => Some(
+            t! {
+                en: "scrcpy was not found at {}, pick a replacement:",
+                zh: "在 {} 未找到 scrcpy，请选择替代：",
+                (executable)
+            }
+            .to_string(),
+        ),
+        None => Some(
+            t! {
+                en: "No scrcpy executable configured, pick one:",
+                zh: "未配置 scrcpy 可执行文件，请选择："
+            }
+            .to_string(),
+        ),
+        _ => None,
+    };
+
+    let adb_placeholder = match &config.detected_adb {
+        Some(detected) => t! {
+            en: "Detected: {}",
+            zh: "已检测到：{}",
+            (detected)
+        }
+        .to_string(),
+        None => t! {
+            en: "adb from PATH",
+            zh: "使用 PATH 中的 adb"
+        }
+        .to_string(),
+    };
+    let adb_path_row = d_row![
+        text(&t! {r
+            en: "adb executable path: ",
+            zh: "adb 可执行文件路径："
+        }),
+        d_text_input!(&adb_placeholder, config.adb.as_deref().unwrap_or_default())
+            .on_input(Message::AdbPathChanged),
+    ];
+
+    let info = match &win_main.scrcpy_info {
+        Some(info) => {
+            let mut line = format!("scrcpy {}", info.version);
+            for (name, version) in &info.libraries {
+                line.push_str(&format!(" · {name} {version}"));
+            }
+            text(line)
+        }
+        None => text(
+            t! {
+                en: "Unable to detect scrcpy version",
+                zh: "无法检测 scrcpy 版本"
+            }
+            .to_string(),
+        ),
+    }
+    .color([0.5, 0.5, 0.5]);
+
+    let mut column = d_column![executable_path_row];
+    if let Some(stale) = stale {
+        column = column.push(text(stale).color([0.9, 0.6, 0.0]));
+    }
+    column
+        .push(installs_row)
+        .push(adb_path_row)
+        .push(info)
+        .into()
 });
@@ -0,0 +1,141 @@
+use crate::config::PerfSummary;
+use crate::ui::Message;
+use crate::{d_button, d_column, d_row, d_sub_title, define_component, t};
+use iced::widget::{container, text, Row, Space};
+use iced::{Alignment, Color, Element};
+
+/// Sessions listed in the history view, most recent first.
+const SHOWN_SESSIONS: usize = 30;
+/// Height of a session's FPS chart.
+const CHART_HEIGHT: f32 = 40.0;
+
+/// A bar per point of the session's FPS chart, scaled to its maximum.
+pub(super) fn fps_chart<'a>(perf: &PerfSummary) -> Element<'a, Message> {
+    let max = perf.max_fps.unwrap_or_default().max(1) as f32;
+    Row::with_children(perf.chart.iter().map(|fps| {
+        container(Space::new())
+            .width(3)
+            .height((CHART_HEIGHT * *fps as f32 / max).max(1.0))
+            .style(|_| container::background(Color::from_rgb(0.3, 0.6, 0.9)))
+            .into()
+    }))
+    .spacing(1)
+    .height(CHART_HEIGHT)
+    .align_y(Alignment::End)
+    .into()
+}
+
+define_component!(history, |_, win_main| {
+    let sub_title = d_row![
+        d_sub_title!(t! {
+            en: "History",
+            zh: "历史记录"
+        }
+        .to_string()),
+        d_button!(if win_main.history.is_some() {
+            t! {en: "Hide", zh: "隐藏"}.to_string()
+        } else {
+            t! {en: "Show", zh: "显示"}.to_string()
+        })
+        .on_press(Message::HistoryToggled),
+    ];
+
+    let column = d_column![sub_title];
+
+    let history = match &win_main.history {
+        None => return column.into(),
+        Some(Err(e)) => {
+            return column
+                .push(
+                    text(
+                        t! {
+                            en: "Unable to read the history: {}",
+                            zh: "无法读取历史记录：{}",
+                            (e)
+                        }
+                        .to_string(),
+                    )
+                    .color([0.9, 0.6, 0.0]),
+                )
+                .into();
+        }
+        Some(Ok(history)) => history,
+    };
+
+    if history.sessions.is_empty() {
+        return column
+            .push(
+                text(
+                    t! {
+                        en: "No sessions yet",
+                        zh: "暂无记录"
+                    }
+                    .to_string(),
+                )
+                .color([0.5, 0.5, 0.5]),
+            )
+            .into();
+    }
+
+    let profile_name = win_main.config_status.input.trim();
+    let mut column = column;
+    for (i, record) in history
+        .sessions
+        .iter()
+        .enumerate()
+        .rev()
+        .take(SHOWN_SESSIONS)
+    {
+        let summary = format!(
+            "{} UTC · {} · {} · {}m {}s · {}",
+            record.started_at(),
+            record.profile.as_deref().unwrap_or("-"),
+            if record.serial.is_empty() {
+                "-"
+            } else {
+                &record.serial
+            },
+            record.duration / 60,
+            record.duration % 60,
+            record.outcome()
+        );
+        let mut summary = text(summary);
+        if !record.succeeded() {
+            summary = summary.color([0.9, 0.6, 0.0]);
+        }
+        column = column.push(d_row![
+            summary,
+            d_button!(t! {en: "Re-run", zh: "重新运行"}.to_string())
+                .on_press(Message::HistoryRerun(i)),
+            d_button!(t! {en: "Save as profile", zh: "另存为配置"}.to_string()).on_press_maybe(
+                (!profile_name.is_empty() && record.config.is_some())
+                    .then_some(Message::HistorySaveAsProfile(i))
+            ),
+        ]);
+        if let Some(perf) = &record.perf {
+            column = column
+                .push(text(perf.to_string()).size(12).color([0.5, 0.5, 0.5]))
+                .push(fps_chart(perf));
+        }
+        if !record.log_tail.is_empty() {
+            column = column.push(
+                text(record.log_tail.join("\n"))
+                    .size(12)
+                    .color([0.5, 0.5, 0.5]),
+            );
+        }
+    }
+
+    column
+        .push(
+            text(
+                t! {
+                    en: "Profiles are saved under the name in \"Save as\" above.",
+                    zh: "配置将以上方“另存为”中的名称保存。"
+                }
+                .to_string(),
+            )
+            .color([0.5, 0.5, 0.5]),
+        )
+        .into()
+});
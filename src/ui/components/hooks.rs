@@ -0,0 +1,76 @@
+use crate::ui::{typed_input, Message, StateButton};
+use crate::util::Field;
+use crate::{d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use iced::widget::text;
+
+define_component!(hooks, |config, win_main| {
+    let sub_title = d_sub_title!(t! {
+        en: "Hooks",
+        zh: "钩子"
+    }
+    .to_string(),);
+
+    let pre_launch = d_row![
+        text(
+            t! {
+                en: "Before launch: ",
+                zh: "启动前："
+            }
+            .to_string()
+        ),
+        d_text_input!("", &config.default.pre_launch_hook)
+            .width(500)
+            .on_input(Message::PreLaunchHookChanged),
+    ];
+
+    let post_exit = d_row![
+        text(
+            t! {
+                en: "After exit: ",
+                zh: "退出后："
+            }
+            .to_string()
+        ),
+        d_text_input!("", &config.default.post_exit_hook)
+            .width(500)
+            .on_input(Message::PostExitHookChanged),
+    ];
+
+    let options = d_row![
+        text(
+            t! {
+                en: "Timeout: ",
+                zh: "超时："
+            }
+            .to_string()
+        ),
+        typed_input(
+            &win_main.drafts,
+            Field::HookTimeout,
+            "30",
+            Some(config.default.hook_timeout),
+            60,
+            Some(Message::HookTimeoutChanged),
+        ),
+        text("s"),
+        text(
+            t! {
+                en: "If the pre-launch hook fails: ",
+                zh: "启动前钩子失败时："
+            }
+            .to_string()
+        ),
+        StateButton::pick_list(config.default.hook_failure, Message::HookFailureChanged),
+    ];
+
+    let hint = text(
+        t! {
+            en: "Hooks get SCRCPY_SERIAL, SCRCPY_PROFILE, SCRCPY_EXECUTABLE, SCRCPY_RECORD_PATH, SCRCPY_V4L2_SINK, and SCRCPY_EXIT_CODE after exit.",
+            zh: "钩子可使用 SCRCPY_SERIAL、SCRCPY_PROFILE、SCRCPY_EXECUTABLE、SCRCPY_RECORD_PATH、SCRCPY_V4L2_SINK，退出后还有 SCRCPY_EXIT_CODE。"
+        }
+        .to_string(),
+    )
+    .color([0.5, 0.5, 0.5]);
+
+    d_column![sub_title, pre_launch, post_exit, options, hint].into()
+});
@@ -1,24 +1,36 @@
-mod exe_info;
 mod action_section;
-mod connect_method;
 mod audio;
-mod video;
-mod performance;
+mod clipboard;
+mod component;
+mod config;
+mod connect_method;
 mod control;
+mod device;
+mod environment;
+mod exe_info;
+mod history;
+mod hooks;
+mod monitor;
+mod others;
 mod output;
+mod performance;
+mod video;
 mod virtual_display;
-mod others;
-mod component;
-mod config;
 
-pub use exe_info::*;
 pub use action_section::*;
-pub use connect_method::*;
 pub use audio::*;
-pub use video::*;
-pub use performance::*;
+pub use clipboard::*;
+pub use config::*;
+pub use connect_method::*;
 pub use control::*;
+pub use device::*;
+pub use environment::*;
+pub use exe_info::*;
+pub use history::*;
+pub use hooks::*;
+pub use monitor::*;
+pub use others::*;
 pub use output::*;
+pub use performance::*;
+pub use video::*;
 pub use virtual_display::*;
-pub use others::*;
-pub use config::*;
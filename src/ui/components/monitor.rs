@@ -0,0 +1,60 @@
+use super::history::fps_chart;
+use crate::ui::Message;
+use crate::util::SessionEnd;
+use crate::{d_button, d_column, d_row, d_sub_title, define_component, t};
+use iced::widget::text;
+
+define_component!(monitor, |_, win_main| {
+    let Some(monitor) = &win_main.monitor else {
+        return d_column![].into();
+    };
+
+    let end = monitor.end();
+    let state = match &end {
+        None => match monitor.current_fps() {
+            Some(fps) => t! {en: "Running · {} fps", zh: "运行中 · {} fps", (fps)}.to_string(),
+            None => t! {en: "Running", zh: "运行中"}.to_string(),
+        },
+        Some(SessionEnd::Exited(Some(code))) => {
+            t! {en: "Exited with code {}", zh: "已退出，代码 {}", (code)}.to_string()
+        }
+        Some(SessionEnd::Exited(None)) => t! {en: "Exited", zh: "已退出"}.to_string(),
+        Some(SessionEnd::Stopped) => t! {en: "Stopped", zh: "已停止"}.to_string(),
+        Some(SessionEnd::Failed(e)) => t! {en: "Failed: {}", zh: "失败：{}", (e)}.to_string(),
+    };
+    let action = if end.is_none() {
+        d_button!(t! {en: "Stop", zh: "停止"}.to_string()).on_press(Message::MonitorStop)
+    } else {
+        d_button!(t! {en: "Close", zh: "关闭"}.to_string()).on_press(Message::MonitorClose)
+    };
+
+    let mut column = d_column![d_row![
+        d_sub_title!(t! {
+            en: "Monitor",
+            zh: "监视器"
+        }
+        .to_string()),
+        text(state),
+        action,
+    ]];
+    match monitor.summary() {
+        Some(perf) => {
+            column = column
+                .push(text(perf.to_string()).size(12).color([0.5, 0.5, 0.5]))
+                .push(fps_chart(&perf));
+        }
+        None => {
+            column = column.push(
+                text(
+                    t! {
+                        en: "Waiting for scrcpy to print the FPS",
+                        zh: "等待 scrcpy 输出帧率"
+                    }
+                    .to_string(),
+                )
+                .color([0.5, 0.5, 0.5]),
+            );
+        }
+    }
+    column.into()
+});
@@ -1,8 +1,9 @@
-use crate::ui::{Message, StateButton};
+use crate::config::OptionKey;
+use crate::ui::{option_field, Message, StateButton};
 use crate::{d_button, d_column, d_row, d_sub_title, d_text_input, define_component, t};
-use iced::widget::{checkbox, text};
+use iced::widget::{button, checkbox, scrollable, text};
 
-define_component!(others, |config, _| {
+define_component!(others, |config, win_main| {
     let sub_title = d_sub_title!(t! {
         en: "Others",
         zh: "其他"
@@ -20,69 +21,81 @@ define_component!(others, |config, _| {
         StateButton::button(config.default.app_name_type, Message::AppNameTypeChanged),
         d_text_input!("", &config.default.start_app)
             .width(200)
-            .on_input(Message::StartAppChanged),
+            .on_input_maybe(
+                win_main
+                    .supports("--start-app")
+                    .then_some(Message::StartAppChanged)
+            ),
         checkbox(
             t! {en: "Restart if running", zh: "如果正在运行则重启"}.to_string(),
             config.default.restart_app,
         )
         .on_toggle(Message::RestartAppChanged),
-    ];
-
-    let time_limit = d_row![
-        text(
-            t! {
-                en: "Time limit: ",
-                zh: "时间限制："
-            }
-            .to_string()
+        d_button!(t! {en: "Browse", zh: "浏览"}.to_string()).on_press_maybe(
+            win_main
+                .supports("--start-app")
+                .then_some(Message::AppBrowserToggled)
         ),
-        d_text_input!(
-            "",
-            &match &config.default.time_limit {
-                Some(time_limit) => time_limit.to_string(),
-                None => "".to_string(),
-            },
-        )
-        .width(100)
-        .on_input(Message::TimeLimitChanged),
-        text("s")
     ];
 
-    let stay_awake = checkbox(
-        t! {en: "Stay awake (device)", zh: "保持唤醒（设备）"}.to_string(),
-        config.default.stay_awake,
-    )
-    .on_toggle(Message::StayAwakeChanged);
-
-    let disable_window = checkbox(
-        t! {en: "Disable window", zh: "禁用窗口"}.to_string(),
-        config.default.disable_window,
-    )
-    .on_toggle(Message::DisableWindowChanged);
-
-    let borderless = checkbox(
-        t! {en: "Borderless", zh: "无边框"}.to_string(),
-        config.default.borderless,
-    )
-    .on_toggle(Message::BorderlessChanged);
-
-    let always_on_top = checkbox(
-        t! {en: "Always on top", zh: "窗口置顶"}.to_string(),
-        config.default.always_on_top,
-    )
-    .on_toggle(Message::AlwaysOnTopChanged);
-
-    let fullscreen = checkbox(
-        t! {en: "Fullscreen", zh: "全屏"}.to_string(),
-        config.default.fullscreen,
-    )
-    .on_toggle(Message::FullscreenChanged);
-
-    let disable_screensaver = checkbox(
-        t! {en: "Disable screensaver (PC)", zh: "禁用屏保（电脑）"}.to_string(),
-        config.default.disable_screensaver,
-    )
-    .on_toggle(Message::DisableScreensaverChanged);
+    let mut app_browser = d_column![];
+    if win_main.app_browser.open {
+        app_browser = app_browser.push(d_row![
+            d_text_input!(
+                &t! {
+                    en: "Search by name or package",
+                    zh: "按名称或包名搜索"
+                },
+                &win_main.app_browser.search
+            )
+            .width(400)
+            .on_input(Message::AppSearchChanged),
+            d_button!(t! {en: "Refresh", zh: "刷新"}.to_string())
+                .on_press_maybe((!win_main.app_browser.loading).then_some(Message::RefreshApps)),
+        ]);
+        let apps = win_main
+            .app_browser
+            .cache
+            .get(&config.default.serial)
+            .cloned()
+            .unwrap_or_default();
+        if win_main.app_browser.loading {
+            app_browser = app_browser.push(
+                text(
+                    t! {
+                        en: "Loading apps...",
+                        zh: "正在加载应用…"
+                    }
+                    .to_string(),
+                )
+                .color([0.5, 0.5, 0.5]),
+            );
+        } else if apps.is_empty() {
+            app_browser = app_browser.push(
+                text(
+                    t! {
+                        en: "No apps found",
+                        zh: "未找到应用"
+                    }
+                    .to_string(),
+                )
+                .color([0.5, 0.5, 0.5]),
+            );
+        } else {
+            let mut list = d_column![];
+            for app in apps
+                .into_iter()
+                .filter(|app| app.matches(&win_main.app_browser.search))
+            {
+                list = list.push(
+                    button(text(app.to_string()))
+                        .style(button::text)
+                        .on_press(Message::AppPicked(app)),
+                );
+            }
+            app_browser = app_browser.push(scrollable(list).height(200));
+        }
+    }
 
     let additional_args = d_row![
         text(
@@ -107,11 +120,18 @@ define_component!(others, |config, _| {
     d_column![
         sub_title,
         start_app,
-        time_limit,
-        d_row![stay_awake],
-        d_row![disable_window, borderless],
-        d_row![always_on_top, fullscreen],
-        disable_screensaver,
+        app_browser,
+        option_field(OptionKey::TimeLimit, win_main),
+        option_field(OptionKey::StayAwake, win_main),
+        d_row![
+            option_field(OptionKey::DisableWindow, win_main),
+            option_field(OptionKey::Borderless, win_main),
+        ],
+        d_row![
+            option_field(OptionKey::AlwaysOnTop, win_main),
+            option_field(OptionKey::Fullscreen, win_main),
+        ],
+        option_field(OptionKey::DisableScreensaver, win_main),
         additional_args,
         reset
     ]
@@ -1,9 +1,10 @@
-use crate::config::{AudioSource, VideoSource};
-use crate::ui::Message;
-use crate::{d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use crate::config::{AudioSource, OptionKey, VideoSource};
+use crate::ui::option_field;
+use crate::util::{estimated_size, format_size};
+use crate::{d_column, d_row, d_sub_title, define_component, t};
 use iced::widget::text;
 
-define_component!(output, |config, _| {
+define_component!(output, |config, win_main| {
     let sub_title = d_sub_title!(t! {
         en: "Output",
         zh: "输出"
@@ -12,7 +13,9 @@ define_component!(output, |config, _| {
 
     let column = d_column![sub_title];
 
-    if config.default.video_source == VideoSource::No && config.default.audio_source == AudioSource::No {
+    if config.default.video_source == VideoSource::No
+        && config.default.audio_source == AudioSource::No
+    {
         return column
             .push(
                 text(
@@ -27,31 +30,28 @@ define_component!(output, |config, _| {
             .into();
     }
 
-    let record = d_row![
-        text(
-            t! {
-                en: "Record file: ",
-                zh: "录制文件："
+    let record = option_field(OptionKey::Record, win_main);
+    let mut column = column.push(record);
+    if !config.default.options.record.trim().is_empty() {
+        let size = match config.default.options.time_limit {
+            Some(seconds) => t! {
+                en: "Expected size for {} s: ≈ {}",
+                zh: "{} 秒预计大小：约 {}",
+                (seconds, format_size(estimated_size(&config.default, seconds.get().into())))
             }
             .to_string(),
-        ),
-        d_text_input!("", &config.default.record)
-            .width(500)
-            .on_input(Message::RecordChanged),
-    ];
-
-    let v4l2 = d_row![
-        text(
-            t! {
-                en: "V4L2 device: ",
-                zh: "V4L2 设备："
+            None => t! {
+                en: "Expected size: ≈ {} per minute",
+                zh: "预计大小：每分钟约 {}",
+                (format_size(estimated_size(&config.default, 60)))
             }
             .to_string(),
-        ),
-        d_text_input!("", &config.default.v4l2)
-            .width(500)
-            .on_input(Message::V4l2Changed),
-    ];
-
-    column.push(record).push(v4l2).into()
+        };
+        column = column.push(text(size).color([0.5, 0.5, 0.5]));
+    }
+    let mut v4l2 = d_row![option_field(OptionKey::V4l2, win_main)];
+    if !config.default.options.v4l2.trim().is_empty() {
+        v4l2 = v4l2.push(option_field(OptionKey::V4l2Buffer, win_main));
+    }
+    column.push(v4l2).into()
 });
@@ -1,9 +1,10 @@
-use crate::config::{AudioCodec, AudioSource, VideoSource};
-use crate::ui::Message;
-use crate::{d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use crate::config::{AudioCodec, AudioSource, OptionKey, VideoSource};
+use crate::ui::{option_field, typed_input, Message, StateButton};
+use crate::util::{estimated_bandwidth, estimated_size, format_bandwidth, format_size, Field};
+use crate::{d_column, d_row, d_sub_title, define_component, t};
 use iced::widget::{checkbox, text};
 
-define_component!(performance, |config, _| {
+define_component!(performance, |config, win_main| {
     let have_audio = config.default.audio_source != AudioSource::No;
     let have_video = config.default.video_source != VideoSource::No;
 
@@ -47,11 +48,14 @@ define_component!(performance, |config, _| {
                 }
                 .to_string(),
             ))
-            .push(
-                d_text_input!("8M", &config.default.video_bit_rate.to_string())
-                    .on_input(Message::VideoBitRateChanged)
-                    .width(100),
-            );
+            .push(typed_input(
+                &win_main.drafts,
+                Field::Option(OptionKey::VideoBitRate),
+                "8M",
+                config.default.options.video_bit_rate,
+                100,
+                Some(|v| Message::OptionTyped(OptionKey::VideoBitRate, v)),
+            ));
     }
 
     if have_audio && config.default.audio_codec != AudioCodec::Raw {
@@ -63,17 +67,34 @@ define_component!(performance, |config, _| {
                 }
                 .to_string(),
             ))
-            .push(
-                d_text_input!("128K", &config.default.audio_bit_rate.to_string())
-                    .on_input(Message::AudioBitRateChanged)
-                    .width(100),
-            );
+            .push(typed_input(
+                &win_main.drafts,
+                Field::Option(OptionKey::AudioBitRate),
+                "128K",
+                config.default.options.audio_bit_rate,
+                100,
+                Some(|v| Message::OptionTyped(OptionKey::AudioBitRate, v)),
+            ));
     }
 
     if have_video || (have_audio && config.default.audio_codec != AudioCodec::Raw) {
         column = column.push(bit_rate);
     }
 
+    let estimate = text(
+        t! {
+            en: "≈ {} ({} per minute)",
+            zh: "约 {}（每分钟 {}）",
+            (
+                format_bandwidth(estimated_bandwidth(&config.default)),
+                format_size(estimated_size(&config.default, 60))
+            )
+        }
+        .to_string(),
+    )
+    .color([0.5, 0.5, 0.5]);
+    column = column.push(estimate);
+
     if have_video {
         let fps = d_row![
             if config.default.video_source == VideoSource::Camera {
@@ -93,17 +114,18 @@ define_component!(performance, |config, _| {
                     .to_string(),
                 )
             },
-            d_text_input!(
+            typed_input(
+                &win_main.drafts,
+                Field::Fps,
                 "",
-                &match &config.default.fps {
-                    None => "".to_string(),
-                    Some(fps) => fps.to_string(),
-                }
+                config.default.fps,
+                60,
+                Some(Message::FpsChanged),
             )
-            .on_input(Message::FpsChanged)
-            .width(60)
         ];
-        column = column.push(fps);
+        column = column
+            .push(fps)
+            .push(option_field(OptionKey::PrintFps, win_main));
     }
 
     let mut buffer = d_row![text(
@@ -123,18 +145,19 @@ define_component!(performance, |config, _| {
                 }
                 .to_string(),
             ))
-            .push(
-                d_text_input!(
-                    "50",
-                    &match &config.default.video_buffer {
-                        None => "".to_string(),
-                        Some(buffer) => buffer.to_string(),
-                    },
-                )
-                .on_input(Message::VideoBufferChanged)
-                .width(52),
-            )
-            .push(text("ms"));
+            .push(typed_input(
+                &win_main.drafts,
+                Field::VideoBuffer,
+                "50",
+                config.default.video_buffer,
+                52,
+                Some(Message::VideoBufferChanged),
+            ))
+            .push(text("ms"))
+            .push(StateButton::pick_list(
+                config.default.buffer_flag,
+                Message::BufferFlagChanged,
+            ));
     }
     if have_audio {
         buffer = buffer
@@ -145,17 +168,14 @@ define_component!(performance, |config, _| {
                 }
                 .to_string(),
             ))
-            .push(
-                d_text_input!(
-                    "50",
-                    &match &config.default.audio_buffer {
-                        None => "".to_string(),
-                        Some(buffer) => buffer.to_string(),
-                    },
-                )
-                .on_input(Message::AudioBufferChanged)
-                .width(52),
-            )
+            .push(typed_input(
+                &win_main.drafts,
+                Field::Option(OptionKey::AudioBuffer),
+                "50",
+                config.default.options.audio_buffer,
+                52,
+                Some(Message::AudioBufferChanged),
+            ))
             .push(text("ms"));
     }
     if have_video && have_audio {
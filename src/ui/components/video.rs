@@ -1,9 +1,12 @@
-use crate::config::{OrientationAngle, OrientationType, VideoSource};
-use crate::ui::{Message, StateButton};
-use crate::{d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use crate::config::{
+    OptionKey, OrientationAngle, OrientationType, RenderDriver, VideoCodec, VideoSource,
+};
+use crate::ui::{option_field, typed_input, ButtonState, Message, StateButton};
+use crate::util::Field;
+use crate::{d_column, d_pick_list, d_row, d_sub_title, d_text_input, define_component, t};
 use iced::widget::{checkbox, text};
 
-define_component!(video, |config, _| {
+define_component!(video, |config, win_main| {
     let sub_title = d_sub_title!(t! {
         en: "Video",
         zh: "视频"
@@ -20,22 +23,19 @@ define_component!(video, |config, _| {
             }
             .to_string()
         ),
-        StateButton::pick_list(config.default.video_source, Message::VideoSourceChanged)
+        d_pick_list!(
+            VideoSource::available(win_main.device_info.as_ref().map(|d| d.sdk)),
+            Some(config.default.video_source),
+            Message::VideoSourceChanged
+        )
     ];
 
     if config.default.video_source == VideoSource::No {
         return column.push(source).into();
     }
 
-    let camera_size = match &config.default.video_size {
-        None => "".to_string(),
-        Some(size) => size.to_string(),
-    };
     if config.default.video_source == VideoSource::Camera {
-        source = source.push(StateButton::pick_list(
-            config.default.camera,
-            Message::CameraChanged,
-        ))
+        source = source.push(option_field(OptionKey::Camera, win_main))
     }
 
     source = source
@@ -46,17 +46,19 @@ define_component!(video, |config, _| {
             }
             .to_string(),
         ))
-        .push(
-            d_text_input!(
-                &t! {
-                    en: "longest side, e.g. 1920",
-                    zh: "最长边，例如 1920"
-                },
-                &camera_size,
-            )
-            .on_input(Message::VideoSizeChanged)
-            .width(180),
-        )
+        .push(typed_input(
+            &win_main.drafts,
+            Field::Option(OptionKey::VideoSize),
+            &t! {
+                en: "longest side, e.g. 1920",
+                zh: "最长边，例如 1920"
+            },
+            config.default.options.video_size,
+            180,
+            win_main
+                .supports("--max-size")
+                .then_some(|v| Message::OptionTyped(OptionKey::VideoSize, v)),
+        ))
         .push(
             checkbox(
                 t! {
@@ -90,6 +92,39 @@ define_component!(video, |config, _| {
             .on_input(Message::VideoCodecOptionsChanged)
     ];
 
+    let mut codec_fallback = d_row![text(
+        t! {
+            en: "Fall back to: ",
+            zh: "失败时改用："
+        }
+        .to_string()
+    )];
+    for codec in VideoCodec::states() {
+        if codec != config.default.video_codec {
+            codec_fallback = codec_fallback.push(
+                checkbox(
+                    codec.to_string(),
+                    config.default.video_codec_fallback.contains(&codec),
+                )
+                .on_toggle(move |enabled| Message::VideoCodecFallbackChanged(codec, enabled)),
+            );
+        }
+    }
+    if !config.default.video_codec_fallback.is_empty() {
+        let chain: Vec<String> = std::iter::once(config.default.video_codec)
+            .chain(
+                config
+                    .default
+                    .video_codec_fallback
+                    .iter()
+                    .copied()
+                    .filter(|c| *c != config.default.video_codec),
+            )
+            .map(|c| c.to_string())
+            .collect();
+        codec_fallback = codec_fallback.push(text(chain.join(" → ")).color([0.5, 0.5, 0.5]));
+    }
+
     let mut orientation = d_row![
         text(
             t! {
@@ -98,8 +133,14 @@ define_component!(video, |config, _| {
             }
             .to_string(),
         ),
-        StateButton::button(config.default.orientation_type, Message::OrientationTypeChanged),
-        StateButton::pick_list(config.default.orientation_angle, Message::OrientationAngleChanged)
+        StateButton::button(
+            config.default.orientation_type,
+            Message::OrientationTypeChanged
+        ),
+        StateButton::pick_list(
+            config.default.orientation_angle,
+            Message::OrientationAngleChanged
+        )
     ];
 
     if config.default.orientation_type == OrientationType::Capture {
@@ -130,5 +171,47 @@ define_component!(video, |config, _| {
         );
     }
 
-    column.push(source).push(codec).push(orientation).into()
+    let angle = d_row![
+        text((OptionKey::Angle.spec().label)()),
+        typed_input(
+            &win_main.drafts,
+            Field::Option(OptionKey::Angle),
+            "",
+            config.default.options.angle,
+            80,
+            win_main
+                .supports("--angle")
+                .then_some(|v| Message::OptionTyped(OptionKey::Angle, v)),
+        ),
+        text("°")
+    ];
+
+    let column = column
+        .push(source)
+        .push(codec)
+        .push(codec_fallback)
+        .push(orientation)
+        .push(angle);
+
+    if !config.default.video_playback {
+        return column.into();
+    }
+
+    let renderer = d_row![
+        text(
+            t! {
+                en: "Render driver: ",
+                zh: "渲染驱动："
+            }
+            .to_string(),
+        ),
+        d_pick_list!(
+            RenderDriver::available(),
+            Some(config.default.render_driver),
+            Message::RenderDriverChanged
+        ),
+        option_field(OptionKey::Mipmaps, win_main)
+    ];
+
+    column.push(renderer).into()
 });
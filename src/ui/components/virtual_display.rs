@@ -1,9 +1,10 @@
-use crate::config::VideoSource;
-use crate::ui::{Message, StateButton};
-use crate::{d_column, d_row, d_sub_title, d_text_input, define_component, t};
+use crate::config::{OptionKey, VideoSource};
+use crate::ui::{option_field, typed_input, Message};
+use crate::util::Field;
+use crate::{d_column, d_row, d_sub_title, define_component, t};
 use iced::widget::{checkbox, text};
 
-define_component!(virtual_display, |config, _| {
+define_component!(virtual_display, |config, win_main| {
     let sub_title = d_sub_title!(t! {
         en: "Virtual display",
         zh: "虚拟显示器"
@@ -27,7 +28,7 @@ define_component!(virtual_display, |config, _| {
             .into();
     }
 
-    let enable_virtual_display = checkbox(
+    let mut enable_virtual_display = d_row![checkbox(
         t! {
             en: "Enable virtual display",
             zh: "启用虚拟显示器"
@@ -35,18 +36,28 @@ define_component!(virtual_display, |config, _| {
         .to_string(),
         config.default.virtual_display,
     )
-    .on_toggle(Message::VirtualDisplayChanged);
+    .on_toggle_maybe(
+        win_main
+            .supports("--new-display")
+            .then_some(Message::VirtualDisplayChanged)
+    )];
+    if let Some(hint) = win_main.requirement_hint("--new-display") {
+        enable_virtual_display = enable_virtual_display.push(text(hint).color([0.5, 0.5, 0.5]));
+    }
 
-    let display_orientation = if config.default.display_width >= config.default.display_height {
-        String::from(&t! {
+    let display_orientation = match (config.default.display_width, config.default.display_height) {
+        (Some(width), Some(height)) if width >= height => String::from(&t! {
             en: "landscape",
             zh: "横屏"
-        })
-    } else {
-        String::from(&t! {
+        }),
+        (Some(_), Some(_)) => String::from(&t! {
             en: "portrait",
             zh: "竖屏"
-        })
+        }),
+        _ => String::from(&t! {
+            en: "blank for the size of the main display",
+            zh: "留空则使用主显示器的尺寸"
+        }),
     };
 
     let display_size = d_row![
@@ -57,39 +68,29 @@ define_component!(virtual_display, |config, _| {
             }
             .to_string(),
         ),
-        d_text_input!("", &config.default.display_width.to_string())
-            .width(80)
-            .on_input(Message::DisplayWidthChanged),
+        typed_input(
+            &win_main.drafts,
+            Field::DisplayWidth,
+            "",
+            config.default.display_width,
+            80,
+            Some(Message::DisplayWidthChanged),
+        ),
         text("x"),
-        d_text_input!("", &config.default.display_height.to_string())
-            .width(80)
-            .on_input(Message::DisplayHeightChanged),
+        typed_input(
+            &win_main.drafts,
+            Field::DisplayHeight,
+            "",
+            config.default.display_height,
+            80,
+            Some(Message::DisplayHeightChanged),
+        ),
         text(display_orientation.to_string())
     ];
 
-    let ime_policy = d_row![
-        text(
-            t! {
-                en: "IME policy: ",
-                zh: "输入法策略："
-            }
-            .to_string(),
-        ),
-        StateButton::pick_list(
-            config.default.display_ime_policy,
-            Message::DisplayImePolicyChanged
-        )
-    ];
+    let ime_policy = option_field(OptionKey::DisplayImePolicy, win_main);
 
-    let destroy_app_on_close = checkbox(
-        t! {
-            en: "Destroy app on close",
-            zh: "关闭时销毁应用"
-        }
-        .to_string(),
-        config.default.destroy_app_on_close,
-    )
-    .on_toggle(Message::DestroyAppOnCloseChanged);
+    let destroy_app_on_close = option_field(OptionKey::DestroyAppOnClose, win_main);
 
     column = column.push(enable_virtual_display);
     if config.default.virtual_display {
@@ -1,81 +1,124 @@
 use crate::config::{
-    AppNameType, AudioCodec, AudioSource, Camera, ConfigItemRaw, ConnectMethod, DisplayImePolicy,
-    Gamepad, Keyboard, Mouse, OrientationAngle, OrientationType, VideoCodec, VideoSource,
+    AppNameType, AudioCodec, AudioSource, BufferFlag, Config, ConfigItemRaw, ConnectMethod,
+    DeviceRule, History, HookFailure, KeyInject, Keyboard, Mouse, MouseBind, OptionKey,
+    OptionValue, OrientationAngle, OrientationType, Preset, RenderDriver, ScrcpyOptions, Seconds,
+    ShortcutMod, VideoCodec, VideoSource, DEFAULT_RECONNECT_RETRIES,
 };
 use crate::i18n::{Language, LANGUAGE};
 use crate::ui::{components, style_default};
-use crate::util::{build_args, select_config_valid, ConfigStatus};
-use crate::{d_hr, t, ARGS, CONFIG};
+use crate::util::{
+    build_args, export_launcher, log, option_supported, requirement_hint, select_config_valid, Adb,
+    App, AppBrowser, ConfigStatus, Device, DeviceInfo, Drafts, Field, LauncherFormat, Monitor,
+    ScrcpyInfo, ScrcpyInstall,
+};
+use crate::{d_hr, t};
 use iced::widget::container::Id;
 use iced::widget::{column, container, scrollable};
 use iced::window::close;
-use iced::{window, Element, Size, Subscription, Task};
+use iced::{time, window, Element, Size, Subscription, Task};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// What the window hands back to `main` when scrcpy should be started.
+pub struct Launch {
+    pub config: Config,
+    pub args: String,
+    /// Name of the saved config the session was started from, if any.
+    pub profile: Option<String>,
+}
 
 pub struct WinMain {
+    pub(crate) config: Config,
     pub(crate) args: String,
     size: Size,
     pub(crate) config_status: ConfigStatus,
-}
-
-impl Default for WinMain {
-    fn default() -> Self {
-        Self {
-            args: build_args(),
-            size: Size {
-                width: 800.0,
-                height: 600.0,
-            },
-            config_status: ConfigStatus::default(),
-        }
-    }
+    /// Text typed into the fields of typed values, see [`Drafts`].
+    pub(crate) drafts: Drafts,
+    pub(crate) advanced_connection: bool,
+    pub(crate) devices: Vec<Device>,
+    pub(crate) device_info: Option<DeviceInfo>,
+    rule_applied_for: Option<String>,
+    pub(crate) app_browser: AppBrowser,
+    /// The history while its view is open, or why it couldn't be read.
+    pub(crate) history: Option<Result<History, String>>,
+    /// The version of the selected scrcpy, once detected.
+    pub(crate) scrcpy_info: Option<ScrcpyInfo>,
+    pub(crate) scrcpy_installs: Vec<ScrcpyInstall>,
+    /// The session started with `--print-fps`, charted while the window
+    /// stays open.
+    pub(crate) monitor: Option<Monitor>,
+    launch: Arc<Mutex<Option<Launch>>>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ExecutablePathChanged(String),
+    DetectScrcpy,
+    /// `scrcpy --version` of the executable it was run for.
+    ScrcpyDetected(Option<String>, Option<ScrcpyInfo>),
+    ScanScrcpyInstalls,
+    ScrcpyInstallsFound(Vec<ScrcpyInstall>),
+    ScrcpyInstallPicked(ScrcpyInstall),
+    AdbPathChanged(String),
+    RefreshDevices,
+    DevicesListed(Vec<Device>),
+    DeviceSelected(Device),
+    DeviceProbed(Option<DeviceInfo>),
+    DeviceInfoTick,
     ConnectMethodChanged(ConnectMethod),
+    AdvancedConnectionToggled,
+    PortChanged(String),
+    OptionChanged(OptionKey, OptionValue),
+    /// Text typed into the field of a number option.
+    OptionTyped(OptionKey, String),
+    AutoReconnectChanged(bool),
+    ReconnectRetriesChanged(String),
     VideoSourceChanged(VideoSource),
-    CameraChanged(Camera),
-    VideoSizeChanged(String),
     VideoPlaybackChanged(bool),
     VideoCodecChanged(VideoCodec),
     VideoCodecOptionsChanged(String),
+    VideoCodecFallbackChanged(VideoCodec, bool),
     OrientationTypeChanged(OrientationType),
     OrientationAngleChanged(OrientationAngle),
     OrientationLockChanged(bool),
     OrientationFlipChanged(bool),
+    RenderDriverChanged(RenderDriver),
     AudioSourceChanged(AudioSource),
-    AudioDupChanged(bool),
     AudioPlaybackChanged(bool),
     AudioCodecChanged(AudioCodec),
     AudioCodecOptionsChanged(String),
-    VideoBitRateChanged(String),
-    AudioBitRateChanged(String),
+    AudioCodecFallbackChanged(AudioCodec, bool),
     FpsChanged(String),
     VideoBufferChanged(String),
+    BufferFlagChanged(BufferFlag),
     AudioBufferChanged(String),
     BufferSyncChanged(bool),
     KeyboardChanged(Keyboard),
     MouseChanged(Mouse),
-    GamepadChanged(Gamepad),
-    RecordChanged(String),
-    V4l2Changed(String),
+    ShortcutModChanged(ShortcutMod, bool),
+    KeyInjectChanged(KeyInject),
+    CustomMouseBindChanged(bool),
+    MouseBindChanged(usize, MouseBind),
     VirtualDisplayChanged(bool),
     DisplayHeightChanged(String),
     DisplayWidthChanged(String),
-    DisplayImePolicyChanged(DisplayImePolicy),
-    DestroyAppOnCloseChanged(bool),
     StartAppChanged(String),
     RestartAppChanged(bool),
     AppNameTypeChanged(AppNameType),
-    TimeLimitChanged(String),
-    StayAwakeChanged(bool),
-    DisableWindowChanged(bool),
-    BorderlessChanged(bool),
-    AlwaysOnTopChanged(bool),
-    FullscreenChanged(bool),
-    DisableScreensaverChanged(bool),
+    AppBrowserToggled,
+    RefreshApps,
+    AppsListed(String, Vec<App>),
+    AppSearchChanged(String),
+    AppPicked(App),
     AdditionalArgsChanged(String),
+    PreLaunchHookChanged(String),
+    PostExitHookChanged(String),
+    HookTimeoutChanged(String),
+    HookFailureChanged(HookFailure),
+    EnvKeyChanged(usize, String),
+    EnvValueChanged(usize, String),
+    EnvAdded,
+    EnvRemoved(usize),
 
     ConfigSelectChanged(String),
     ConfigSelectSave,
@@ -83,15 +126,61 @@ pub enum Message {
     ConfigSelectLoad,
     ConfigInputChanged(String),
     ConfigSave,
+    PresetSelected(&'static Preset),
+    PresetApply,
+    PresetClone,
+    ExportFormatChanged(LauncherFormat),
+    ExportViaWrapperChanged(bool),
+    ExportLauncher,
+    DeviceRuleAdd,
+    DeviceRuleDelete(usize),
+
+    HistoryToggled,
+    HistoryRerun(usize),
+    HistorySaveAsProfile(usize),
 
     LanguageChanged(Language),
 
     ArgsChanged(String),
     Reset,
     Run,
+    MonitorTick,
+    MonitorStop,
+    MonitorClose,
     Resize(Size),
 }
 impl WinMain {
+    pub fn new(config: Config, launch: Arc<Mutex<Option<Launch>>>) -> (Self, Task<Message>) {
+        let win_main = Self {
+            args: build_args(&config.default, None),
+            config,
+            size: Size {
+                width: 800.0,
+                height: 600.0,
+            },
+            config_status: ConfigStatus::default(),
+            drafts: Drafts::default(),
+            advanced_connection: false,
+            devices: vec![],
+            device_info: None,
+            rule_applied_for: None,
+            app_browser: AppBrowser::default(),
+            history: None,
+            scrcpy_info: None,
+            scrcpy_installs: vec![],
+            monitor: None,
+            launch,
+        };
+        (
+            win_main,
+            Task::batch([
+                Task::done(Message::RefreshDevices),
+                Task::done(Message::DetectScrcpy),
+                Task::done(Message::ScanScrcpyInstalls),
+            ]),
+        )
+    }
+
     pub fn title(&self) -> String {
         String::from(&t! {
             en: "Scrcpy Config",
@@ -102,220 +191,338 @@ impl WinMain {
     pub fn update(&mut self, message: Message) -> impl Into<Task<Message>> {
         match message {
             Message::ExecutablePathChanged(path) => {
-                CONFIG.write().unwrap().default.executable = Some(path);
-                self.args = build_args();
+                self.config.default.executable = Some(path);
+            }
+            Message::DetectScrcpy => return self.detect_scrcpy(),
+            Message::ScrcpyDetected(executable, info) => {
+                if executable == self.config.default.executable {
+                    self.scrcpy_info = info;
+                    self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+                }
+            }
+            Message::ScanScrcpyInstalls => {
+                return Task::perform(
+                    async { ScrcpyInstall::discover() },
+                    Message::ScrcpyInstallsFound,
+                );
+            }
+            Message::ScrcpyInstallsFound(installs) => {
+                self.scrcpy_installs = installs;
+            }
+            Message::ScrcpyInstallPicked(install) => {
+                self.config.default.executable = Some(install.path);
+                return self.detect_scrcpy();
+            }
+            Message::AdbPathChanged(path) => {
+                self.config.adb = if path.trim().is_empty() {
+                    None
+                } else {
+                    Some(path)
+                };
+            }
+            Message::RefreshDevices => {
+                let adb = Adb::new(&self.config);
+                return Task::perform(async move { Device::list(&adb) }, Message::DevicesListed);
+            }
+            Message::DevicesListed(devices) => {
+                self.devices = devices;
+                let serial = self.config.default.serial.clone();
+                if self.devices.iter().any(|d| d.serial == serial) {
+                    let adb = Adb::new(&self.config);
+                    return Task::perform(
+                        async move { DeviceInfo::probe(&adb, &serial) },
+                        Message::DeviceProbed,
+                    );
+                }
+                self.device_info = None;
+            }
+            Message::DeviceSelected(device) => {
+                self.config.default.serial = device.serial.clone();
+                self.device_info = None;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+                let adb = Adb::new(&self.config);
+                return Task::perform(
+                    async move { DeviceInfo::probe(&adb, &device.serial) },
+                    Message::DeviceProbed,
+                );
+            }
+            Message::DeviceInfoTick => {
+                let serial = self.config.default.serial.clone();
+                let adb = Adb::new(&self.config);
+                return Task::perform(
+                    async move { DeviceInfo::probe(&adb, &serial) },
+                    Message::DeviceProbed,
+                );
+            }
+            Message::DeviceProbed(info) => {
+                let serial = self.config.default.serial.clone();
+                self.device_info = info.filter(|i| i.serial == serial);
+                if let Some(info) = self.device_info.clone() {
+                    if self.rule_applied_for.as_ref() != Some(&info.serial) {
+                        self.rule_applied_for = Some(info.serial.clone());
+                        return self.apply_device_rule(&info);
+                    }
+                }
             }
             Message::ConnectMethodChanged(method) => {
-                CONFIG.write().unwrap().default.connect_method = method;
-                self.args = build_args();
+                self.config.default.connect_method = method;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::AdvancedConnectionToggled => {
+                self.advanced_connection = !self.advanced_connection;
+            }
+            Message::PortChanged(port) => {
+                self.config.default.port = port.trim().to_string();
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::OptionChanged(key, value) => {
+                self.config.default.options.set(key, value);
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::OptionTyped(key, text) => {
+                let value = ScrcpyOptions::parse(key, &text);
+                if let Ok(value) = self.drafts.record(Field::Option(key), text, value) {
+                    self.config.default.options.set(key, value);
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::AutoReconnectChanged(auto_reconnect) => {
+                self.config.default.auto_reconnect = auto_reconnect;
+            }
+            Message::ReconnectRetriesChanged(retries) => {
+                if let Ok(retries) = self.drafts.parse(Field::ReconnectRetries, retries) {
+                    self.config.default.reconnect_retries =
+                        retries.unwrap_or(DEFAULT_RECONNECT_RETRIES);
+                }
             }
             Message::VideoSourceChanged(source) => {
-                CONFIG.write().unwrap().default.video_source = source;
-                self.args = build_args();
-            }
-            Message::CameraChanged(camera) => {
-                CONFIG.write().unwrap().default.camera = camera;
-                self.args = build_args();
-            }
-            Message::VideoSizeChanged(size) => {
-                if size.trim().is_empty() {
-                    CONFIG.write().unwrap().default.video_size = None
-                } else if let Ok(size) = size.parse::<u32>() {
-                    CONFIG.write().unwrap().default.video_size = Some(size)
-                };
-                self.args = build_args();
+                self.config.default.video_source = source;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::VideoPlaybackChanged(display) => {
-                CONFIG.write().unwrap().default.video_playback = display;
-                self.args = build_args();
+                self.config.default.video_playback = display;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::VideoCodecChanged(codec) => {
-                CONFIG.write().unwrap().default.video_codec = codec;
-                self.args = build_args();
+                self.config.default.video_codec = codec;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::VideoCodecOptionsChanged(options) => {
-                CONFIG.write().unwrap().default.video_codec_options = options;
-                self.args = build_args();
+                self.config.default.video_codec_options = options;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::VideoCodecFallbackChanged(codec, enabled) => {
+                let config = &mut self.config;
+                config.default.video_codec_fallback.retain(|c| *c != codec);
+                if enabled {
+                    config.default.video_codec_fallback.push(codec);
+                }
             }
             Message::OrientationTypeChanged(orientation) => {
-                CONFIG.write().unwrap().default.orientation_type = orientation;
-                self.args = build_args();
+                self.config.default.orientation_type = orientation;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::OrientationAngleChanged(angle) => {
-                CONFIG.write().unwrap().default.orientation_angle = angle;
-                self.args = build_args();
+                self.config.default.orientation_angle = angle;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::OrientationLockChanged(lock) => {
-                CONFIG.write().unwrap().default.orientation_lock = lock;
-                self.args = build_args();
+                self.config.default.orientation_lock = lock;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::OrientationFlipChanged(flip) => {
-                CONFIG.write().unwrap().default.orientation_flip = flip;
-                self.args = build_args();
+                self.config.default.orientation_flip = flip;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::AudioSourceChanged(source) => {
-                CONFIG.write().unwrap().default.audio_source = source;
-                self.args = build_args();
+            Message::RenderDriverChanged(driver) => {
+                self.config.default.render_driver = driver;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::AudioDupChanged(dup) => {
-                CONFIG.write().unwrap().default.audio_dup = dup;
-                self.args = build_args();
+            Message::AudioSourceChanged(source) => {
+                self.config.default.audio_source = source;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::AudioPlaybackChanged(play) => {
-                CONFIG.write().unwrap().default.audio_playback = play;
-                self.args = build_args();
+                self.config.default.audio_playback = play;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::AudioCodecChanged(codec) => {
-                CONFIG.write().unwrap().default.audio_codec = codec;
-                self.args = build_args();
+                self.config.default.audio_codec = codec;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::AudioCodecOptionsChanged(options) => {
-                CONFIG.write().unwrap().default.audio_codec_options = options;
-                self.args = build_args();
-            }
-            Message::VideoBitRateChanged(rate) => {
-                CONFIG.write().unwrap().default.video_bit_rate = rate.trim().to_string();
-                self.args = build_args();
+                self.config.default.audio_codec_options = options;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::AudioBitRateChanged(rate) => {
-                CONFIG.write().unwrap().default.audio_bit_rate = rate.trim().to_string();
-                self.args = build_args();
+            Message::AudioCodecFallbackChanged(codec, enabled) => {
+                let config = &mut self.config;
+                config.default.audio_codec_fallback.retain(|c| *c != codec);
+                if enabled {
+                    config.default.audio_codec_fallback.push(codec);
+                }
             }
             Message::FpsChanged(fps) => {
-                if fps.trim().is_empty() {
-                    CONFIG.write().unwrap().default.fps = None
-                } else if let Ok(size) = fps.parse::<u32>() {
-                    CONFIG.write().unwrap().default.fps = Some(size)
-                };
-                self.args = build_args();
+                if let Ok(fps) = self.drafts.parse(Field::Fps, fps) {
+                    self.config.default.fps = fps;
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::VideoBufferChanged(buffer) => {
-                if buffer.trim().is_empty() {
-                    CONFIG.write().unwrap().default.video_buffer = None;
-                    if CONFIG.read().unwrap().default.buffer_sync {
-                        CONFIG.write().unwrap().default.audio_buffer = None;
+                if let Ok(buffer) = self.drafts.parse(Field::VideoBuffer, buffer) {
+                    self.config.default.video_buffer = buffer;
+                    if self.config.default.buffer_sync {
+                        self.config.default.options.audio_buffer = buffer;
+                        self.drafts.remove(Field::Option(OptionKey::AudioBuffer));
                     }
-                } else if let Ok(size) = buffer.parse::<u32>() {
-                    CONFIG.write().unwrap().default.video_buffer = Some(size);
-                    if CONFIG.read().unwrap().default.buffer_sync {
-                        CONFIG.write().unwrap().default.audio_buffer = Some(size);
-                    }
-                };
-                self.args = build_args();
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::BufferFlagChanged(flag) => {
+                self.config.default.buffer_flag = flag;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::AudioBufferChanged(buffer) => {
-                if buffer.trim().is_empty() {
-                    CONFIG.write().unwrap().default.audio_buffer = None;
-                    if CONFIG.read().unwrap().default.buffer_sync {
-                        CONFIG.write().unwrap().default.video_buffer = None;
-                    }
-                } else if let Ok(size) = buffer.parse::<u32>() {
-                    CONFIG.write().unwrap().default.audio_buffer = Some(size);
-                    if CONFIG.read().unwrap().default.buffer_sync {
-                        CONFIG.write().unwrap().default.video_buffer = Some(size);
+                let field = Field::Option(OptionKey::AudioBuffer);
+                if let Ok(buffer) = self.drafts.parse(field, buffer) {
+                    self.config.default.options.audio_buffer = buffer;
+                    if self.config.default.buffer_sync {
+                        self.config.default.video_buffer = buffer;
+                        self.drafts.remove(Field::VideoBuffer);
                     }
-                };
-                self.args = build_args();
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::BufferSyncChanged(sync) => {
-                CONFIG.write().unwrap().default.buffer_sync = sync;
-                self.args = build_args();
+                self.config.default.buffer_sync = sync;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::KeyboardChanged(keyboard) => {
-                CONFIG.write().unwrap().default.keyboard = keyboard;
-                self.args = build_args();
+                self.config.default.keyboard = keyboard;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::MouseChanged(mouse) => {
-                CONFIG.write().unwrap().default.mouse = mouse;
-                self.args = build_args();
+                self.config.default.mouse = mouse;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::ShortcutModChanged(shortcut_mod, enabled) => {
+                let config = &mut self.config;
+                config.default.shortcut_mod.retain(|m| *m != shortcut_mod);
+                if enabled {
+                    config.default.shortcut_mod.push(shortcut_mod);
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::GamepadChanged(gamepad) => {
-                CONFIG.write().unwrap().default.gamepad = gamepad;
-                self.args = build_args();
+            Message::KeyInjectChanged(key_inject) => {
+                self.config.default.key_inject = key_inject;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::RecordChanged(record) => {
-                CONFIG.write().unwrap().default.record = record;
-                self.args = build_args();
+            Message::CustomMouseBindChanged(custom) => {
+                let config = &mut self.config;
+                config.default.mouse_bind = if custom {
+                    Some(MouseBind::defaults(config.default.mouse))
+                } else {
+                    None
+                };
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::V4l2Changed(v4l2) => {
-                CONFIG.write().unwrap().default.v4l2 = v4l2;
-                self.args = build_args();
+            Message::MouseBindChanged(i, binding) => {
+                if let Some(bindings) = &mut self.config.default.mouse_bind {
+                    bindings[i] = binding;
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::VirtualDisplayChanged(virtual_display) => {
-                CONFIG.write().unwrap().default.virtual_display = virtual_display;
-                self.args = build_args();
+                self.config.default.virtual_display = virtual_display;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::DisplayHeightChanged(height) => {
-                if height.trim().is_empty() {
-                    CONFIG.write().unwrap().default.display_height = 0
-                } else if let Ok(size) = height.parse::<u32>() {
-                    CONFIG.write().unwrap().default.display_height = size
-                };
-                self.args = build_args();
+                if let Ok(height) = self.drafts.parse(Field::DisplayHeight, height) {
+                    self.config.default.display_height = height;
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::DisplayWidthChanged(width) => {
-                if width.trim().is_empty() {
-                    CONFIG.write().unwrap().default.display_width = 0
-                } else if let Ok(size) = width.parse::<u32>() {
-                    CONFIG.write().unwrap().default.display_width = size
-                };
-                self.args = build_args();
-            }
-            Message::DisplayImePolicyChanged(policy) => {
-                CONFIG.write().unwrap().default.display_ime_policy = policy;
-                self.args = build_args();
-            }
-            Message::DestroyAppOnCloseChanged(destroy_app_on_close) => {
-                CONFIG.write().unwrap().default.destroy_app_on_close = destroy_app_on_close;
-                self.args = build_args();
+                if let Ok(width) = self.drafts.parse(Field::DisplayWidth, width) {
+                    self.config.default.display_width = width;
+                }
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::StartAppChanged(start_app) => {
-                CONFIG.write().unwrap().default.start_app = start_app;
-                self.args = build_args();
+                self.config.default.start_app = start_app;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::RestartAppChanged(restart_app) => {
-                CONFIG.write().unwrap().default.restart_app = restart_app;
-                self.args = build_args();
+                self.config.default.restart_app = restart_app;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::AppNameTypeChanged(app_name_type) => {
-                CONFIG.write().unwrap().default.app_name_type = app_name_type;
-                self.args = build_args();
-            }
-            Message::TimeLimitChanged(time_limit) => {
-                if time_limit.trim().is_empty() {
-                    CONFIG.write().unwrap().default.time_limit = None
-                } else if let Ok(size) = time_limit.parse::<u32>() {
-                    CONFIG.write().unwrap().default.time_limit = Some(size)
-                };
-                self.args = build_args();
+                self.config.default.app_name_type = app_name_type;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::StayAwakeChanged(stay_awake) => {
-                CONFIG.write().unwrap().default.stay_awake = stay_awake;
-                self.args = build_args();
+            Message::AppBrowserToggled => {
+                self.app_browser.open = !self.app_browser.open;
+                let serial = self.config.default.serial.clone();
+                if self.app_browser.open && !self.app_browser.cache.contains_key(&serial) {
+                    return self.list_apps();
+                }
+            }
+            Message::RefreshApps => return self.list_apps(),
+            Message::AppsListed(serial, apps) => {
+                self.app_browser.loading = false;
+                // an empty list usually means the device was unreachable
+                if apps.is_empty() {
+                    self.app_browser.cache.remove(&serial);
+                } else {
+                    self.app_browser.cache.insert(serial, apps);
+                }
             }
-            Message::DisableWindowChanged(disable_window) => {
-                CONFIG.write().unwrap().default.disable_window = disable_window;
-                self.args = build_args();
+            Message::AppSearchChanged(search) => {
+                self.app_browser.search = search;
             }
-            Message::BorderlessChanged(borderless) => {
-                CONFIG.write().unwrap().default.borderless = borderless;
-                self.args = build_args();
+            Message::AppPicked(app) => {
+                let config = &mut self.config;
+                config.default.start_app = app.package;
+                config.default.app_name_type = AppNameType::PackageName;
+                self.app_browser.open = false;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::AlwaysOnTopChanged(always_on_top) => {
-                CONFIG.write().unwrap().default.always_on_top = always_on_top;
-                self.args = build_args();
+            Message::AdditionalArgsChanged(args) => {
+                self.config.default.additional_args = args;
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
-            Message::FullscreenChanged(fullscreen) => {
-                CONFIG.write().unwrap().default.fullscreen = fullscreen;
-                self.args = build_args();
+            Message::PreLaunchHookChanged(hook) => {
+                self.config.default.pre_launch_hook = hook;
             }
-            Message::DisableScreensaverChanged(disable_screensaver) => {
-                CONFIG.write().unwrap().default.disable_screensaver = disable_screensaver;
-                self.args = build_args();
+            Message::PostExitHookChanged(hook) => {
+                self.config.default.post_exit_hook = hook;
             }
-            Message::AdditionalArgsChanged(args) => {
-                CONFIG.write().unwrap().default.additional_args = args;
-                self.args = build_args();
+            Message::HookTimeoutChanged(timeout) => {
+                if let Ok(timeout) = self.drafts.parse(Field::HookTimeout, timeout) {
+                    self.config.default.hook_timeout =
+                        timeout.unwrap_or(Seconds::DEFAULT_HOOK_TIMEOUT);
+                }
+            }
+            Message::HookFailureChanged(failure) => {
+                self.config.default.hook_failure = failure;
+            }
+            Message::EnvKeyChanged(i, key) => {
+                if let Some((k, _)) = self.config.default.env.get_mut(i) {
+                    *k = key;
+                }
+            }
+            Message::EnvValueChanged(i, value) => {
+                if let Some((_, v)) = self.config.default.env.get_mut(i) {
+                    *v = value;
+                }
+            }
+            Message::EnvAdded => {
+                self.config.default.env.push((String::new(), String::new()));
+            }
+            Message::EnvRemoved(i) => {
+                let config = &mut self.config;
+                if i < config.default.env.len() {
+                    config.default.env.remove(i);
+                }
             }
 
             Message::ConfigSelectChanged(name) => {
@@ -325,7 +532,7 @@ impl WinMain {
                 if self.config_status.chosen.trim().is_empty() {
                     return Task::none();
                 }
-                let mut config = CONFIG.write().unwrap();
+                let config = &mut self.config;
                 let config_item = config.default.clone();
                 config
                     .saved
@@ -336,20 +543,21 @@ impl WinMain {
                 if self.config_status.chosen.trim().is_empty() {
                     return Task::none();
                 }
-                let mut config = CONFIG.write().unwrap();
+                let config = &mut self.config;
                 config.saved.remove(&self.config_status.chosen);
                 config.to_raw().dump().unwrap();
             }
             Message::ConfigSelectLoad => {
-                let mut config = CONFIG.write().unwrap();
-                if !select_config_valid(&self.config_status.chosen, &config) {
+                let config = &mut self.config;
+                if !select_config_valid(&self.config_status.chosen, config) {
                     return Task::none();
                 }
                 if let Some(c) = config.saved.get(&self.config_status.chosen) {
                     config.default = c.clone();
                 }
-                drop(config);
-                self.args = build_args();
+                self.drafts.clear();
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+                return self.detect_scrcpy();
             }
             Message::ConfigInputChanged(name) => {
                 self.config_status.input = name;
@@ -358,73 +566,351 @@ impl WinMain {
                 if self.config_status.input.trim().is_empty() {
                     return Task::none();
                 }
-                let mut config = CONFIG.write().unwrap();
+                let config = &mut self.config;
                 let config_item = config.default.clone();
                 config
                     .saved
                     .insert(self.config_status.input.trim().into(), config_item);
                 config.to_raw().dump().unwrap();
             }
+            Message::PresetSelected(preset) => {
+                self.config_status.preset = Some(preset);
+                self.config_status.preset_saved = None;
+            }
+            Message::PresetApply => {
+                let Some(preset) = self.config_status.preset else {
+                    return Task::none();
+                };
+                match preset.apply(&self.config.default) {
+                    Ok(config) => self.config.default = config,
+                    Err(e) => {
+                        log(&format!("unable to apply preset {}: {e}", preset.key));
+                        return Task::none();
+                    }
+                }
+                self.drafts.clear();
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+            }
+            Message::PresetClone => {
+                let Some(preset) = self.config_status.preset else {
+                    return Task::none();
+                };
+                // named after the "Save as" input, or the preset's key
+                let name = match self.config_status.input.trim() {
+                    "" => preset.key.to_string(),
+                    name => name.to_string(),
+                };
+                let config = &mut self.config;
+                if config.saved.contains_key(&name) {
+                    self.config_status.preset_saved = Some(
+                        t! {
+                            en: "A config named {} already exists, enter another name under Save as",
+                            zh: "已存在名为 {} 的配置，请在“另存为”中输入其他名称",
+                            (name)
+                        }
+                        .to_string(),
+                    );
+                    return Task::none();
+                }
+                match preset.apply(&config.default) {
+                    Ok(item) => config.saved.insert(name.clone(), item),
+                    Err(e) => {
+                        log(&format!("unable to apply preset {}: {e}", preset.key));
+                        return Task::none();
+                    }
+                };
+                config.to_raw().dump().unwrap();
+                self.config_status.preset_saved = Some(
+                    t! {
+                        en: "Saved as {}",
+                        zh: "已保存为 {}",
+                        (name)
+                    }
+                    .to_string(),
+                );
+                self.config_status.chosen = name;
+            }
+            Message::ExportFormatChanged(format) => {
+                self.config_status.export_format = format;
+            }
+            Message::ExportViaWrapperChanged(via_wrapper) => {
+                self.config_status.export_via_wrapper = via_wrapper;
+            }
+            Message::ExportLauncher => {
+                let status = &mut self.config_status;
+                if !select_config_valid(&status.chosen, &self.config) {
+                    return Task::none();
+                }
+                let wrapper = status
+                    .export_via_wrapper
+                    .then(|| std::env::current_exe().ok())
+                    .flatten();
+                let exported = export_launcher(
+                    status.export_format,
+                    &status.chosen,
+                    &self.config,
+                    self.scrcpy_info.as_ref(),
+                    wrapper.as_deref(),
+                );
+                status.exported = Some(match exported {
+                    Ok(path) => t! {
+                        en: "Exported to {}",
+                        zh: "已导出到 {}",
+                        (path.display())
+                    }
+                    .to_string(),
+                    Err(e) => t! {
+                        en: "Export failed: {}",
+                        zh: "导出失败：{}",
+                        (e)
+                    }
+                    .to_string(),
+                });
+            }
+            Message::DeviceRuleAdd => {
+                let config = &mut self.config;
+                if !select_config_valid(&self.config_status.chosen, config) {
+                    return Task::none();
+                }
+                let serial = config.default.serial.clone();
+                if serial.is_empty() {
+                    return Task::none();
+                }
+                config.rules.retain(|rule| {
+                    rule.serial.as_ref() != Some(&serial)
+                        || rule.model.is_some()
+                        || rule.min_sdk.is_some()
+                });
+                config.rules.insert(
+                    0,
+                    DeviceRule {
+                        serial: Some(serial.clone()),
+                        model: None,
+                        min_sdk: None,
+                        profile: self.config_status.chosen.clone(),
+                    },
+                );
+                config.to_raw().dump().unwrap();
+                self.rule_applied_for = Some(serial);
+            }
+            Message::DeviceRuleDelete(i) => {
+                let config = &mut self.config;
+                if i < config.rules.len() {
+                    config.rules.remove(i);
+                    config.to_raw().dump().unwrap();
+                }
+            }
+
+            Message::HistoryToggled => {
+                self.history = match self.history {
+                    Some(_) => None,
+                    None => Some(History::load().map_err(|e| e.to_string())),
+                };
+            }
+            Message::HistoryRerun(i) => {
+                let Some(record) = self
+                    .history
+                    .as_ref()
+                    .and_then(|h| h.as_ref().ok())
+                    .and_then(|h| h.sessions.get(i))
+                else {
+                    return Task::none();
+                };
+                let mut config = self.config.clone();
+                if let Some(item) = record.config.as_ref().map(|c| c.to_config()) {
+                    config.default = item;
+                }
+                if let Some(executable) = record.argv.first() {
+                    config.default.executable = Some(executable.clone());
+                }
+                *self.launch.lock().unwrap() = Some(Launch {
+                    config,
+                    args: shell_words::join(record.argv.iter().skip(1)),
+                    profile: record.profile.clone(),
+                });
+                return window::get_latest().then(|id| close(id.unwrap()));
+            }
+            Message::HistorySaveAsProfile(i) => {
+                let name = self.config_status.input.trim().to_string();
+                let Some(item) = self
+                    .history
+                    .as_ref()
+                    .and_then(|h| h.as_ref().ok())
+                    .and_then(|h| h.sessions.get(i))
+                    .and_then(|record| record.config.as_ref())
+                    .map(|c| c.to_config())
+                else {
+                    return Task::none();
+                };
+                if name.is_empty() {
+                    return Task::none();
+                }
+                let config = &mut self.config;
+                config.saved.insert(name.clone(), item);
+                config.to_raw().dump().unwrap();
+                self.config_status.chosen = name;
+            }
 
             Message::LanguageChanged(language) => {
-                CONFIG.write().unwrap().default.language = language;
+                self.config.default.language = language;
                 *LANGUAGE.write().unwrap() = language;
-                self.args = build_args();
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
 
             Message::ArgsChanged(command) => {
                 self.args = command;
             }
             Message::Reset => {
-                let mut c = ConfigItemRaw::default().to_config(false).unwrap();
-                c.language = *LANGUAGE.read().unwrap();
-                CONFIG.write().unwrap().default = c;
-                CONFIG.read().unwrap().to_raw().dump().unwrap();
-                self.args = build_args();
+                let mut c = ConfigItemRaw::default().to_config();
+                c.executable = self.config.default.executable.take();
+                self.config.default = c;
+                self.drafts.clear();
+                self.config.to_raw().dump().unwrap();
+                self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
             }
             Message::Resize(size) => {
                 self.size = size;
             }
             Message::Run => {
-                *ARGS.write().unwrap() = Some(self.args.clone());
+                // the config still holds the last valid values
+                if self.drafts.has_errors(&self.config.default) {
+                    return Task::none();
+                }
+                let profile = select_config_valid(&self.config_status.chosen, &self.config)
+                    .then(|| self.config_status.chosen.clone());
+                if self.config.default.options.print_fps {
+                    if self.monitor.as_ref().is_some_and(|m| m.end().is_none()) {
+                        return Task::none();
+                    }
+                    self.config.to_raw().dump().unwrap();
+                    self.monitor = Some(Monitor::start(&self.config, self.args.clone(), profile));
+                    return Task::none();
+                }
+                *self.launch.lock().unwrap() = Some(Launch {
+                    config: self.config.clone(),
+                    args: self.args.clone(),
+                    profile,
+                });
                 return window::get_latest().then(|id| close(id.unwrap()));
             }
+            Message::MonitorTick => {
+                // the session is recorded once it ended
+                let ended = self.monitor.as_ref().is_some_and(|m| m.end().is_some());
+                if ended && self.history.is_some() {
+                    self.history = Some(History::load().map_err(|e| e.to_string()));
+                }
+            }
+            Message::MonitorStop => {
+                if let Some(monitor) = &self.monitor {
+                    monitor.stop();
+                }
+            }
+            Message::MonitorClose => {
+                self.monitor = None;
+            }
         };
         ().into()
     }
 
+    /// Loads the saved profile matching `info`, keeping the selected serial.
+    fn apply_device_rule(&mut self, info: &DeviceInfo) -> Task<Message> {
+        let Some((name, profile)) =
+            self.config
+                .profile_for_device(&info.serial, &info.model, info.sdk)
+        else {
+            return Task::none();
+        };
+        let (name, mut profile) = (name.to_string(), profile.clone());
+        profile.serial = info.serial.clone();
+        self.config.default = profile;
+        self.drafts.clear();
+        self.config_status.chosen = name;
+        self.args = build_args(&self.config.default, self.scrcpy_info.as_ref());
+        self.detect_scrcpy()
+    }
+
+    /// Lists the apps of the selected device for the app browser.
+    fn list_apps(&mut self) -> Task<Message> {
+        self.app_browser.loading = true;
+        let serial = self.config.default.serial.clone();
+        let adb = Adb::new(&self.config);
+        let executable = self.config.default.executable.clone();
+        Task::perform(
+            async move {
+                let apps = App::list(&adb, executable.as_deref(), &serial);
+                (serial, apps)
+            },
+            |(serial, apps)| Message::AppsListed(serial, apps),
+        )
+    }
+
+    /// Whether the selected scrcpy accepts `option`, see [`option_supported`].
+    pub(crate) fn supports(&self, option: &str) -> bool {
+        option_supported(self.scrcpy_info.as_ref(), option)
+    }
+
+    /// A note for widgets whose option the selected scrcpy lacks.
+    pub(crate) fn requirement_hint(&self, option: &str) -> Option<String> {
+        requirement_hint(self.scrcpy_info.as_ref(), option)
+    }
+
+    /// Runs `scrcpy --version` off the UI thread; the arguments are rebuilt
+    /// once the version is known.
+    fn detect_scrcpy(&self) -> Task<Message> {
+        let executable = self.config.default.executable.clone();
+        Task::perform(
+            async move {
+                let info = executable.as_deref().and_then(ScrcpyInfo::detect);
+                (executable, info)
+            },
+            |(executable, info)| Message::ScrcpyDetected(executable, info),
+        )
+    }
+
     pub fn view(&self) -> Element<Message> {
-        let config = CONFIG.try_read().unwrap();
+        let config = &self.config;
 
         let config_section = {
             column![
-                components::exe_info(&config, self),
-                components::connect_method(&config, self),
+                components::exe_info(config, self),
+                components::connect_method(config, self),
+                d_hr!(),
+                components::device(config, self),
+                d_hr!(),
+                components::video(config, self),
                 d_hr!(),
-                components::video(&config, self),
+                components::audio(config, self),
                 d_hr!(),
-                components::audio(&config, self),
+                components::performance(config, self),
                 d_hr!(),
-                components::performance(&config, self),
+                components::control(config, self),
                 d_hr!(),
-                components::control(&config, self),
+                components::clipboard(config, self),
                 d_hr!(),
-                components::output(&config, self),
+                components::output(config, self),
                 d_hr!(),
-                components::virtual_display(&config, self),
+                components::virtual_display(config, self),
                 d_hr!(),
-                components::others(&config, self),
+                components::others(config, self),
                 d_hr!(),
-                components::config(&config, self),
+                components::hooks(config, self),
+                d_hr!(),
+                components::environment(config, self),
+                d_hr!(),
+                components::config(config, self),
+                d_hr!(),
+                components::history(config, self),
             ]
             .padding(style_default::Padding::page())
             .spacing(style_default::Spacing::general())
         };
 
+        let monitor_height = if self.monitor.is_some() { 100.0 } else { 0.0 };
         container(
             column![
-                scrollable(config_section).height(self.size.height - 100.0),
-                components::action_section(&config, self)
+                scrollable(config_section).height(self.size.height - 100.0 - monitor_height),
+                components::monitor(config, self),
+                components::action_section(config, self)
             ]
             .padding(style_default::Padding::container()),
         )
@@ -433,6 +919,151 @@ impl WinMain {
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        window::resize_events().map(|size| Message::Resize(size.1))
+        let resize = window::resize_events().map(|size| Message::Resize(size.1));
+        let mut subscriptions = vec![resize];
+        if self.device_info.is_some() {
+            subscriptions
+                .push(time::every(Duration::from_secs(10)).map(|_| Message::DeviceInfoTick));
+        }
+        // scrcpy prints the FPS once a second
+        if self.monitor.as_ref().is_some_and(|m| m.end().is_none()) {
+            subscriptions.push(time::every(Duration::from_secs(1)).map(|_| Message::MonitorTick));
+        }
+        Subscription::batch(subscriptions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BitRate, ConfigRaw, Millis, UnitError};
+    use crate::util::SessionEnd;
+
+    /// Keeps the config and history the handlers save out of the user's.
+    fn isolate_config() {
+        static ONCE: std::sync::Once = std::sync::Once::new();
+        ONCE.call_once(|| {
+            let dir =
+                std::env::temp_dir().join(format!("scrcpy-wrapper-test-{}", std::process::id()));
+            std::env::set_var("XDG_CONFIG_HOME", dir);
+        });
+    }
+
+    fn win_main() -> WinMain {
+        isolate_config();
+        let config = ConfigRaw::default().to_config();
+        WinMain::new(config, Arc::default()).0
+    }
+
+    #[test]
+    fn option_change_updates_args() {
+        let mut win_main = win_main();
+        let _ = win_main.update(Message::OptionChanged(
+            OptionKey::AlwaysOnTop,
+            OptionValue::Switch(true),
+        ));
+        assert!(win_main.config.default.options.always_on_top);
+        assert!(win_main.args.contains("--always-on-top"));
+    }
+
+    #[test]
+    fn synced_buffers_follow_each_other() {
+        let mut win_main = win_main();
+        let _ = win_main.update(Message::BufferSyncChanged(true));
+        let _ = win_main.update(Message::VideoBufferChanged("50".to_string()));
+        assert_eq!(
+            win_main.config.default.options.audio_buffer,
+            Millis::new(50).ok()
+        );
+        let _ = win_main.update(Message::AudioBufferChanged("".to_string()));
+        assert_eq!(win_main.config.default.video_buffer, None);
+    }
+
+    #[test]
+    fn invalid_text_keeps_the_last_value() {
+        let mut win_main = win_main();
+        let field = Field::Option(OptionKey::VideoBitRate);
+        let _ = win_main.update(Message::OptionTyped(OptionKey::VideoBitRate, "4M".into()));
+        let _ = win_main.update(Message::OptionTyped(OptionKey::VideoBitRate, "4MB".into()));
+        let rate = win_main.config.default.options.video_bit_rate;
+        assert_eq!(rate, BitRate::new(4_000_000));
+        assert_eq!(win_main.drafts.text(field, rate), "4MB");
+        assert_eq!(win_main.drafts.error(field), Some(UnitError::BitRateSyntax));
+        assert!(win_main.args.contains("--video-bit-rate=4M"));
+
+        let _ = win_main.update(Message::OptionTyped(OptionKey::TunnelPort, "70000".into()));
+        assert_eq!(win_main.config.default.options.tunnel_port, None);
+        assert_eq!(
+            win_main.drafts.error(Field::Option(OptionKey::TunnelPort)),
+            Some(UnitError::OutOfRange { min: 0, max: 65535 })
+        );
+
+        let _ = win_main.update(Message::DisplayWidthChanged(" ".to_string()));
+        assert_eq!(win_main.config.default.display_width, None);
+        assert_eq!(win_main.drafts.error(Field::DisplayWidth), None);
+    }
+
+    #[test]
+    fn run_is_refused_while_a_field_is_invalid() {
+        let mut win_main = win_main();
+        let _ = win_main.update(Message::FpsChanged("sixty".into()));
+        let _ = win_main.update(Message::Run);
+        assert!(win_main.launch.lock().unwrap().is_none());
+        let _ = win_main.update(Message::FpsChanged("60".into()));
+        let _ = win_main.update(Message::Run);
+        assert!(win_main.launch.lock().unwrap().take().is_some());
+
+        // hidden along with the video, so it can't be fixed and doesn't count
+        let _ = win_main.update(Message::FpsChanged("sixty".into()));
+        let _ = win_main.update(Message::VideoSourceChanged(VideoSource::No));
+        let _ = win_main.update(Message::Run);
+        assert!(win_main.launch.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn run_hands_over_config() {
+        let mut win_main = win_main();
+        let _ = win_main.update(Message::OptionChanged(
+            OptionKey::Control,
+            OptionValue::Switch(false),
+        ));
+        let _ = win_main.update(Message::Run);
+        let launch = win_main.launch.lock().unwrap().take().unwrap();
+        assert!(!launch.config.default.options.control);
+        assert_eq!(launch.args, win_main.args);
+        assert_eq!(launch.profile, None);
+    }
+
+    #[test]
+    fn run_with_fps_monitor_keeps_the_window() {
+        let mut win_main = win_main();
+        let _ = win_main.update(Message::OptionChanged(
+            OptionKey::PrintFps,
+            OptionValue::Switch(true),
+        ));
+        let _ = win_main.update(Message::Run);
+        assert!(win_main.launch.lock().unwrap().is_none());
+        let monitor = win_main.monitor.as_ref().unwrap();
+        // no scrcpy is configured, so the session fails right away
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while monitor.end().is_none() {
+            assert!(std::time::Instant::now() < deadline, "session didn't end");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(monitor.end(), Some(SessionEnd::Failed(_))));
+        let _ = win_main.update(Message::MonitorClose);
+        assert!(win_main.monitor.is_none());
+    }
+
+    #[test]
+    fn preset_clone_keeps_existing_configs() {
+        let mut win_main = win_main();
+        let mine = win_main.config.default.clone();
+        win_main.config.saved.insert("gaming".into(), mine.clone());
+        let _ = win_main.update(Message::PresetSelected(Preset::find("gaming").unwrap()));
+        let _ = win_main.update(Message::PresetClone);
+        assert_eq!(win_main.config.saved["gaming"], mine);
+        assert!(win_main.config_status.preset_saved.is_some());
+        assert_eq!(win_main.config_status.chosen, "");
     }
 }
@@ -1,8 +1,12 @@
-mod main;
 mod button;
-mod style;
 mod components;
+mod main;
+mod option_field;
+mod style;
+mod typed_input;
 
-pub use main::*;
 pub use button::*;
-pub use style::*;
\ No newline at end of file
+pub use main::*;
+pub use option_field::*;
+pub use style::*;
+pub use typed_input::*;
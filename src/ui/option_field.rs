@@ -0,0 +1,81 @@
+use crate::config::{OptionKey, OptionValue, ScrcpyOptions};
+use crate::ui::{typed_input, Message, WinMain};
+use crate::util::Field;
+use crate::{d_pick_list, d_row, d_text_input};
+use iced::widget::{checkbox, text};
+use iced::Element;
+
+/// The widget for a registered option: a checkbox for a switch, a labelled
+/// pick list for an enum, a labelled input otherwise. Disabled while the
+/// detected scrcpy lacks the flag; a pick list is left out then.
+pub fn option_field<'a>(key: OptionKey, win_main: &WinMain) -> Element<'a, Message> {
+    let spec = key.spec();
+    let supported = win_main.supports(spec.flag);
+    let drafts = &win_main.drafts;
+    let field: Element<'a, Message> = match win_main.config.default.options.get(key) {
+        OptionValue::Switch(value) => checkbox((spec.label)(), value)
+            .on_toggle_maybe(
+                supported.then_some(move |v| Message::OptionChanged(key, OptionValue::Switch(v))),
+            )
+            .into(),
+        OptionValue::Text(value) => d_row![
+            text((spec.label)()),
+            d_text_input!("", &value).width(400).on_input_maybe(
+                supported.then_some(move |v| Message::OptionChanged(key, OptionValue::Text(v)))
+            ),
+        ]
+        .into(),
+        OptionValue::Number(value) => d_row![
+            text((spec.label)()),
+            typed_input(
+                drafts,
+                Field::Option(key),
+                "",
+                value,
+                100,
+                supported.then_some(move |v| Message::OptionTyped(key, v)),
+            ),
+        ]
+        .into(),
+        OptionValue::BitRate(value) => d_row![
+            text((spec.label)()),
+            typed_input(
+                drafts,
+                Field::Option(key),
+                "",
+                value,
+                100,
+                supported.then_some(move |v| Message::OptionTyped(key, v)),
+            ),
+        ]
+        .into(),
+        OptionValue::Decimal(value) => d_row![
+            text((spec.label)()),
+            typed_input(
+                drafts,
+                Field::Option(key),
+                "",
+                value,
+                100,
+                supported.then_some(move |v| Message::OptionTyped(key, v)),
+            ),
+        ]
+        .into(),
+        OptionValue::Choice(value) if supported => {
+            let choices = ScrcpyOptions::choices(key);
+            let selected = choices.iter().find(|c| c.value == value).cloned();
+            d_row![
+                text((spec.label)()),
+                d_pick_list!(choices, selected, move |c| {
+                    Message::OptionChanged(key, OptionValue::Choice(c.value))
+                }),
+            ]
+            .into()
+        }
+        OptionValue::Choice(_) => text((spec.label)()).into(),
+    };
+    match win_main.requirement_hint(spec.flag) {
+        Some(hint) => d_row![field, text(hint).color([0.5, 0.5, 0.5])].into(),
+        None => field,
+    }
+}
@@ -80,6 +80,10 @@ macro_rules! d_row {
 
 #[macro_export]
 macro_rules! d_column {
+    () => {
+        iced::widget::column![]
+            .spacing($crate::ui::style_default::Spacing::general())
+    };
     ($($x:expr),+ $(,)?) => {
         iced::widget::column![$($x,)+]
             .spacing($crate::ui::style_default::Spacing::general())
@@ -0,0 +1,25 @@
+use crate::ui::Message;
+use crate::util::{Drafts, Field};
+use crate::{d_row, d_text_input};
+use iced::widget::text;
+use iced::{Element, Length};
+use std::fmt::Display;
+
+/// The input for a typed value: shows the text as typed and, next to it,
+/// why that text can't be used. Disabled without `on_input`.
+pub fn typed_input<'a>(
+    drafts: &Drafts,
+    field: Field,
+    placeholder: &str,
+    value: Option<impl Display>,
+    width: impl Into<Length>,
+    on_input: Option<impl Fn(String) -> Message + 'a>,
+) -> Element<'a, Message> {
+    let input = d_text_input!(placeholder, &drafts.text(field, value))
+        .width(width)
+        .on_input_maybe(on_input);
+    match drafts.error(field) {
+        Some(e) => d_row![input, text(e.to_string()).color([0.9, 0.6, 0.0])].into(),
+        None => input.into(),
+    }
+}
@@ -0,0 +1,11 @@
+use crate::util::App;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct AppBrowser {
+    pub open: bool,
+    pub loading: bool,
+    pub search: String,
+    /// Apps per device serial; failed or empty listings are not kept.
+    pub cache: HashMap<String, Vec<App>>,
+}
@@ -1,9 +1,18 @@
-use crate::config::Config;
+use crate::config::{Config, Preset};
+use crate::util::LauncherFormat;
 
 #[derive(Debug, Clone, Default)]
-pub struct ConfigStatus{
+pub struct ConfigStatus {
     pub chosen: String,
     pub input: String,
+    pub preset: Option<&'static Preset>,
+    /// Outcome of the last "Save as config" of a preset.
+    pub preset_saved: Option<String>,
+    pub export_format: LauncherFormat,
+    /// Whether exported launchers go through `scrcpy-wrapper run`.
+    pub export_via_wrapper: bool,
+    /// Outcome of the last export, shown below its button.
+    pub exported: Option<String>,
 }
 
 pub fn select_config_valid(chosen: &String, config: &Config) -> bool {
@@ -11,4 +20,4 @@ pub fn select_config_valid(chosen: &String, config: &Config) -> bool {
         return false;
     }
     config.saved.contains_key(chosen)
-}
\ No newline at end of file
+}
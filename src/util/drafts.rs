@@ -0,0 +1,91 @@
+use crate::config::{ConfigItem, ConnectMethod, FieldValue, OptionKey, UnitError, VideoSource};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// The text fields holding typed values such as bit rates or sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    ReconnectRetries,
+    Fps,
+    VideoBuffer,
+    DisplayWidth,
+    DisplayHeight,
+    HookTimeout,
+    Option(OptionKey),
+}
+
+impl Field {
+    /// Whether the window offers the field for `config`; the others are
+    /// hidden or disabled by the options they depend on.
+    pub fn applies(self, config: &ConfigItem) -> bool {
+        match self {
+            Field::ReconnectRetries => {
+                config.auto_reconnect && config.connect_method == ConnectMethod::Adb
+            }
+            Field::Fps | Field::VideoBuffer => config.video_source != VideoSource::No,
+            Field::DisplayWidth | Field::DisplayHeight => {
+                config.video_source == VideoSource::Display && config.virtual_display
+            }
+            Field::HookTimeout => true,
+            Field::Option(key) => (key.spec().applies)(config),
+        }
+    }
+}
+
+/// What was typed into the typed fields, kept as typed while editing so
+/// that invalid text stays visible next to its error instead of being
+/// dropped. The config only ever holds the last valid value.
+#[derive(Debug, Clone, Default)]
+pub struct Drafts(HashMap<Field, (String, Option<UnitError>)>);
+
+impl Drafts {
+    /// Records `text` typed into `field` and parses it, `Ok(None)` when blank.
+    pub fn parse<T: FieldValue>(
+        &mut self,
+        field: Field,
+        text: String,
+    ) -> Result<Option<T>, UnitError> {
+        let value = T::parse_optional(&text);
+        self.record(field, text, value)
+    }
+
+    /// Records `text` typed into `field` along with what it parsed to.
+    pub fn record<T>(
+        &mut self,
+        field: Field,
+        text: String,
+        value: Result<T, UnitError>,
+    ) -> Result<T, UnitError> {
+        self.0.insert(field, (text, value.as_ref().err().copied()));
+        value
+    }
+
+    /// What `field` shows: the text typed into it, else `value`.
+    pub fn text(&self, field: Field, value: Option<impl Display>) -> String {
+        match self.0.get(&field) {
+            Some((text, _)) => text.clone(),
+            None => value.map(|v| v.to_string()).unwrap_or_default(),
+        }
+    }
+
+    pub fn error(&self, field: Field) -> Option<UnitError> {
+        self.0.get(&field).and_then(|(_, error)| *error)
+    }
+
+    /// Whether a field offered for `config` holds text that was rejected.
+    pub fn has_errors(&self, config: &ConfigItem) -> bool {
+        self.0
+            .iter()
+            .any(|(field, (_, error))| error.is_some() && field.applies(config))
+    }
+
+    /// Forgets the text of `field`, e.g. after its value was set elsewhere.
+    pub fn remove(&mut self, field: Field) {
+        self.0.remove(&field);
+    }
+
+    /// Forgets all typed text, when another profile is loaded.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
@@ -1,5 +1,10 @@
-mod args;
+mod app_browser;
 mod config_status;
+mod drafts;
+mod monitor;
 
-pub use args::*;
-pub use config_status::*;
\ No newline at end of file
+pub use app_browser::*;
+pub use config_status::*;
+pub use drafts::*;
+pub use monitor::*;
+pub use scrcpy_wrapper_core::util::*;
@@ -0,0 +1,69 @@
+use crate::config::{Config, PerfSummary};
+use crate::util::{child_env, log, Adb, Launcher, PerfMonitor, SessionEnd, Stopper};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// A session started from the window with `--print-fps`, which stays open
+/// to chart it live. Dropping it stops the session.
+pub struct Monitor {
+    perf: Arc<Mutex<PerfMonitor>>,
+    stopper: Stopper,
+    end: Arc<Mutex<Option<SessionEnd>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Monitor {
+    pub fn start(config: &Config, args: String, profile: Option<String>) -> Self {
+        let item = config.default.clone();
+        let executable = item.executable.clone().unwrap_or_default();
+        let env = child_env(config.adb(), &item);
+        let mut launcher = Launcher::new(executable, args, item, profile, env);
+        let adb = Adb::new(config);
+        let (perf, stopper) = (launcher.perf(), launcher.stopper());
+        let end = Arc::new(Mutex::new(None));
+        let thread = thread::spawn({
+            let end = end.clone();
+            move || {
+                let finished = launcher.run_session(&adb);
+                if let SessionEnd::Failed(e) = &finished {
+                    log(&format!("session failed: {e}"));
+                }
+                *end.lock().unwrap() = Some(finished);
+            }
+        });
+        Self {
+            perf,
+            stopper,
+            end,
+            thread: Some(thread),
+        }
+    }
+
+    /// The statistics so far, see [`PerfMonitor::summary`].
+    pub fn summary(&self) -> Option<PerfSummary> {
+        self.perf.lock().unwrap().summary()
+    }
+
+    /// The FPS scrcpy printed last.
+    pub fn current_fps(&self) -> Option<u32> {
+        self.perf.lock().unwrap().samples().last().copied()
+    }
+
+    /// How the session ended, `None` while it runs.
+    pub fn end(&self) -> Option<SessionEnd> {
+        self.end.lock().unwrap().clone()
+    }
+
+    pub fn stop(&self) {
+        self.stopper.stop();
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.stop();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}